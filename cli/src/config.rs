@@ -6,12 +6,24 @@ pub struct Config {
     pub prompt: String,
     pub enable_colors: bool,
     pub coulomb_and_farad: bool,
+    pub bankers_rounding: bool,
+    pub auto_prefix: bool,
+    pub unit_system: UnitSystem,
+    pub unit_definitions: String,
     pub colors: color::OutputColors,
     pub max_history_size: usize,
     unknown_settings: UnknownSettings,
     unknown_keys: Vec<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Default,
+    Metric,
+    Imperial,
+    SiBase,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum UnknownSettings {
     Ignore,
@@ -37,6 +49,10 @@ impl<'de> serde::Deserialize<'de> for Config {
                 let mut seen_prompt = false;
                 let mut seen_enable_colors = false;
                 let mut seen_coulomb_farad = false;
+                let mut seen_bankers_rounding = false;
+                let mut seen_auto_prefix = false;
+                let mut seen_unit_system = false;
+                let mut seen_unit_definitions = false;
                 let mut seen_colors = false;
                 let mut seen_max_hist_size = false;
                 while let Some(key) = map.next_key()? {
@@ -72,6 +88,46 @@ impl<'de> serde::Deserialize<'de> for Config {
                             result.coulomb_and_farad = map.next_value()?;
                             seen_coulomb_farad = true;
                         }
+                        "bankers-rounding" => {
+                            if seen_bankers_rounding {
+                                return Err(serde::de::Error::duplicate_field("bankers-rounding"));
+                            }
+                            result.bankers_rounding = map.next_value()?;
+                            seen_bankers_rounding = true;
+                        }
+                        "auto-prefix" => {
+                            if seen_auto_prefix {
+                                return Err(serde::de::Error::duplicate_field("auto-prefix"));
+                            }
+                            result.auto_prefix = map.next_value()?;
+                            seen_auto_prefix = true;
+                        }
+                        "unit-system" => {
+                            if seen_unit_system {
+                                return Err(serde::de::Error::duplicate_field("unit-system"));
+                            }
+                            let unit_system: &str = map.next_value()?;
+                            result.unit_system = match unit_system {
+                                "default" => UnitSystem::Default,
+                                "metric" => UnitSystem::Metric,
+                                "imperial" => UnitSystem::Imperial,
+                                "si-base" => UnitSystem::SiBase,
+                                v => {
+                                    return Err(serde::de::Error::invalid_value(
+                                        serde::de::Unexpected::Str(v),
+                                        &"`default`, `metric`, `imperial` or `si-base`",
+                                    ))
+                                }
+                            };
+                            seen_unit_system = true;
+                        }
+                        "unit-definitions" => {
+                            if seen_unit_definitions {
+                                return Err(serde::de::Error::duplicate_field("unit-definitions"));
+                            }
+                            result.unit_definitions = map.next_value()?;
+                            seen_unit_definitions = true;
+                        }
                         "colors" => {
                             if seen_colors {
                                 return Err(serde::de::Error::duplicate_field("colors"));
@@ -114,6 +170,10 @@ impl<'de> serde::Deserialize<'de> for Config {
             "prompt",
             "enable-colors",
             "coulomb-and-farad",
+            "bankers-rounding",
+            "auto-prefix",
+            "unit-system",
+            "unit-definitions",
             "colors",
             "max-history-size",
             "unknown-settings",
@@ -128,6 +188,10 @@ impl Default for Config {
             prompt: "> ".to_string(),
             enable_colors: use_colors_if_auto(),
             coulomb_and_farad: false,
+            bankers_rounding: false,
+            auto_prefix: false,
+            unit_system: UnitSystem::Default,
+            unit_definitions: String::new(),
             colors: color::OutputColors::default(),
             max_history_size: 1000,
             unknown_settings: UnknownSettings::Warn,
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, time};
+use std::{cell::RefCell, fs, path, time};
 
 use crate::config;
 
@@ -38,8 +38,42 @@ impl InnerCtx {
         if config.coulomb_and_farad {
             res.core_ctx.use_coulomb_and_farad();
         }
+        if config.bankers_rounding {
+            res.core_ctx.use_bankers_rounding();
+        }
+        if config.auto_prefix {
+            res.core_ctx.use_auto_prefix();
+        }
+        match config.unit_system {
+            config::UnitSystem::Default => (),
+            config::UnitSystem::Metric => res.core_ctx.use_metric_units(),
+            config::UnitSystem::Imperial => res.core_ctx.use_imperial_units(),
+            config::UnitSystem::SiBase => res.core_ctx.use_si_base_units(),
+        }
+        if !config.unit_definitions.is_empty() {
+            res.load_unit_definitions(&config.unit_definitions);
+        }
         res
     }
+
+    fn load_unit_definitions(&mut self, path: &str) {
+        let mut path = path::PathBuf::from(path);
+        if path.is_relative() {
+            if let Ok(config_dir) = crate::file_paths::get_config_dir() {
+                path = config_dir.join(path);
+            }
+        }
+        let definitions = match fs::read_to_string(&path) {
+            Ok(definitions) => definitions,
+            Err(e) => {
+                eprintln!("Error: failed to read unit definitions from {path:?}: {e}");
+                return;
+            }
+        };
+        if let Err(e) = self.core_ctx.load_unit_definitions(&definitions) {
+            eprintln!("Error: failed to load unit definitions from {path:?}: {e}");
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -31,7 +31,7 @@ fn get_home_dir() -> Result<path::PathBuf, HomeDirError> {
     Ok(home_dir)
 }
 
-fn get_config_dir() -> Result<path::PathBuf, HomeDirError> {
+pub fn get_config_dir() -> Result<path::PathBuf, HomeDirError> {
     // first try $FEND_CONFIG_DIR
     if let Some(env_var_config_dir) = env::var_os("FEND_CONFIG_DIR") {
         return Ok(path::PathBuf::from(env_var_config_dir));
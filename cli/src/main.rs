@@ -57,6 +57,9 @@ fn eval_and_print_res(
                 } else {
                     println!("{}", res.get_main_result());
                 }
+                for warning in res.get_warnings() {
+                    eprintln!("Warning: {warning}");
+                }
             }
             EvalResult::Ok
         }
@@ -2,10 +2,15 @@ use crate::error::{FendError, Interrupt};
 use crate::eval::evaluate_to_value;
 use crate::ident::Ident;
 use crate::interrupt::test_int;
-use crate::num::{Base, FormattingStyle, Number};
+use crate::num::{Base, ComplexFormat, FormattingStyle, Modular, Number};
 use crate::scope::Scope;
-use crate::serialize::{deserialize_u8, serialize_u8};
+use crate::serialize::{
+    deserialize_bool, deserialize_u8, deserialize_usize, serialize_bool, serialize_u8,
+    serialize_usize,
+};
+use crate::units::PrefixRule;
 use crate::value::{built_in_function::BuiltInFunction, ApplyMulHandling, Value};
+use std::borrow::Cow;
 use std::sync::Arc;
 use std::{fmt, io};
 
@@ -28,6 +33,8 @@ pub(crate) enum Bop {
     Mod,
     Pow,
     Bitwise(BitwiseBop),
+    // complex number from magnitude and angle, e.g. `5 ∠ 30°`
+    Polar,
 }
 
 impl Bop {
@@ -45,6 +52,7 @@ impl Bop {
             Self::Bitwise(BitwiseBop::Xor) => 9,
             Self::Bitwise(BitwiseBop::LeftShift) => 10,
             Self::Bitwise(BitwiseBop::RightShift) => 11,
+            Self::Polar => 12,
         };
         serialize_u8(n, write)?;
         Ok(())
@@ -64,6 +72,7 @@ impl Bop {
             9 => Self::Bitwise(BitwiseBop::Xor),
             10 => Self::Bitwise(BitwiseBop::LeftShift),
             11 => Self::Bitwise(BitwiseBop::RightShift),
+            12 => Self::Polar,
             _ => return Err(FendError::DeserializationError),
         })
     }
@@ -84,6 +93,7 @@ impl fmt::Display for Bop {
             Self::Bitwise(BitwiseBop::Xor) => " xor ",
             Self::Bitwise(BitwiseBop::LeftShift) => "<<",
             Self::Bitwise(BitwiseBop::RightShift) => ">>",
+            Self::Polar => " \u{2220} ",
         };
         write!(f, "{s}")
     }
@@ -113,9 +123,33 @@ pub(crate) enum Expr {
 
     Assign(Ident, Box<Expr>),
     Statements(Box<Expr>, Box<Expr>),
+    // Solve `lhs = rhs` (or `lhs = 0` if there is no rhs) for the given unknown
+    Solve(Ident, Box<Self>, Option<Box<Self>>),
+    // Integer in the ring of integers modulo n, e.g. `5 (mod 13)`
+    Modular(Box<Self>, Box<Self>),
+    // List of values, e.g. `[1, 2, 3]`
+    List(Vec<Self>),
+    // Unit definition, e.g. `unit l@smoot = 67 in`, or a new base unit
+    // if there is no definition
+    UnitDefinition(Ident, PrefixRule, Option<Box<Self>>),
+    // Documentation for a unit, e.g. `describe kelvin` or `help kelvin`
+    Describe(Ident),
+    // Evaluate with the given pixel density, e.g. `1920 px to cm at 96 dpi`
+    AtPixelDensity(Box<Self>, Box<Self>),
 }
 
 impl Expr {
+    /// Returns whether the result of this expression is explicitly converted,
+    /// e.g. with `to mF`
+    pub(crate) fn is_conversion(&self) -> bool {
+        match self {
+            Self::As(_, _) | Self::AtPixelDensity(_, _) => true,
+            Self::Parens(x) | Self::Statements(_, x) => x.is_conversion(),
+            _ => false,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
     pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
         match self {
             Self::Literal(x) => {
@@ -192,6 +226,45 @@ impl Expr {
                 a.serialize(write)?;
                 b.serialize(write)?;
             }
+            Self::Solve(a, b, c) => {
+                serialize_u8(16, write)?;
+                a.serialize(write)?;
+                b.serialize(write)?;
+                serialize_bool(c.is_some(), write)?;
+                if let Some(c) = c {
+                    c.serialize(write)?;
+                }
+            }
+            Self::Modular(a, b) => {
+                serialize_u8(17, write)?;
+                a.serialize(write)?;
+                b.serialize(write)?;
+            }
+            Self::List(items) => {
+                serialize_u8(18, write)?;
+                serialize_usize(items.len(), write)?;
+                for item in items {
+                    item.serialize(write)?;
+                }
+            }
+            Self::UnitDefinition(a, b, c) => {
+                serialize_u8(19, write)?;
+                a.serialize(write)?;
+                b.serialize(write)?;
+                serialize_bool(c.is_some(), write)?;
+                if let Some(c) = c {
+                    c.serialize(write)?;
+                }
+            }
+            Self::Describe(a) => {
+                serialize_u8(20, write)?;
+                a.serialize(write)?;
+            }
+            Self::AtPixelDensity(a, b) => {
+                serialize_u8(21, write)?;
+                a.serialize(write)?;
+                b.serialize(write)?;
+            }
         }
         Ok(())
     }
@@ -242,6 +315,41 @@ impl Expr {
                 Box::new(Self::deserialize(read)?),
                 Box::new(Self::deserialize(read)?),
             ),
+            16 => Self::Solve(
+                Ident::deserialize(read)?,
+                Box::new(Self::deserialize(read)?),
+                if deserialize_bool(read)? {
+                    Some(Box::new(Self::deserialize(read)?))
+                } else {
+                    None
+                },
+            ),
+            17 => Self::Modular(
+                Box::new(Self::deserialize(read)?),
+                Box::new(Self::deserialize(read)?),
+            ),
+            18 => Self::List({
+                let len = deserialize_usize(read)?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(Self::deserialize(read)?);
+                }
+                items
+            }),
+            19 => Self::UnitDefinition(
+                Ident::deserialize(read)?,
+                PrefixRule::deserialize(read)?,
+                if deserialize_bool(read)? {
+                    Some(Box::new(Self::deserialize(read)?))
+                } else {
+                    None
+                },
+            ),
+            20 => Self::Describe(Ident::deserialize(read)?),
+            21 => Self::AtPixelDensity(
+                Box::new(Self::deserialize(read)?),
+                Box::new(Self::deserialize(read)?),
+            ),
             _ => return Err(FendError::DeserializationError),
         })
     }
@@ -278,6 +386,34 @@ impl Expr {
             Self::Of(a, b) => format!("{a} of {}", b.format(ctx, int)?),
             Self::Assign(a, b) => format!("{a} = {}", b.format(ctx, int)?),
             Self::Statements(a, b) => format!("{}; {}", a.format(ctx, int)?, b.format(ctx, int)?),
+            Self::Solve(a, b, None) => format!("solve {a}: {}", b.format(ctx, int)?),
+            Self::Solve(a, b, Some(c)) => format!(
+                "solve {a}: {} = {}",
+                b.format(ctx, int)?,
+                c.format(ctx, int)?
+            ),
+            Self::Modular(a, b) => {
+                format!("({} (mod {}))", a.format(ctx, int)?, b.format(ctx, int)?)
+            }
+            Self::List(items) => {
+                let mut res = "[".to_string();
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        res.push_str(", ");
+                    }
+                    res.push_str(&item.format(ctx, int)?);
+                }
+                res.push(']');
+                res
+            }
+            Self::UnitDefinition(a, b, None) => format!("unit {}{a}", b.marker()),
+            Self::UnitDefinition(a, b, Some(c)) => {
+                format!("unit {}{a} = {}", b.marker(), c.format(ctx, int)?)
+            }
+            Self::Describe(a) => format!("describe {a}"),
+            Self::AtPixelDensity(a, b) => {
+                format!("({} at {})", a.format(ctx, int)?, b.format(ctx, int)?)
+            }
         })
     }
 }
@@ -311,15 +447,15 @@ pub(crate) fn evaluate<I: Interrupt>(
 ) -> Result<Value, FendError> {
     macro_rules! eval {
         ($e:expr) => {
-            evaluate($e, scope.clone(), context, int)
+            evaluate($e, scope.clone(), context, int).map(Value::without_note)
         };
     }
     test_int(int)?;
     Ok(match expr {
         Expr::Literal(v) => v,
         Expr::Ident(ident) => resolve_identifier(&ident, scope, context, int)?,
-        Expr::Parens(x) => eval!(*x)?,
-        Expr::UnaryMinus(x) => eval!(*x)?.handle_num(|x| Ok(-x), Expr::UnaryMinus, scope)?,
+        Expr::Parens(x) => evaluate(*x, scope, context, int)?,
+        Expr::UnaryMinus(x) => evaluate_neg(eval!(*x)?, scope, int)?,
         Expr::UnaryPlus(x) => eval!(*x)?.handle_num(Ok, Expr::UnaryPlus, scope)?,
         Expr::UnaryDiv(x) => {
             eval!(*x)?.handle_num(|x| Number::from(1).div(x, int), Expr::UnaryDiv, scope)?
@@ -331,7 +467,11 @@ pub(crate) fn evaluate<I: Interrupt>(
         Expr::Bop(Bop::Minus, a, b) => {
             let a = eval!(*a)?;
             match a {
-                Value::Num(a) => Value::Num(Box::new(a.sub(eval!(*b)?.expect_num()?, int)?)),
+                Value::Num(a) => match eval!(*b)? {
+                    b @ Value::Modular(_) => Value::Num(a).modular_bop(Bop::Minus, b, int)?,
+                    b => Value::Num(Box::new(a.sub(b.expect_num()?, int)?)),
+                },
+                a @ Value::Modular(_) => a.modular_bop(Bop::Minus, eval!(*b)?, int)?,
                 f @ (Value::BuiltInFunction(_) | Value::Fn(_, _, _)) => f.apply(
                     Expr::UnaryMinus(b),
                     ApplyMulHandling::OnlyApply,
@@ -344,6 +484,9 @@ pub(crate) fn evaluate<I: Interrupt>(
         }
         Expr::Bop(Bop::Pow, a, b) => {
             let lhs = eval!(*a)?;
+            if let Value::Modular(_) = lhs {
+                return lhs.modular_bop(Bop::Pow, eval!(*b)?, int);
+            }
             if should_compute_inverse(&*b, int)? {
                 let result = match &lhs {
                     Value::BuiltInFunction(f) => Some(f.invert()?),
@@ -378,13 +521,27 @@ pub(crate) fn evaluate<I: Interrupt>(
                 scope,
             )?
         }
-        Expr::Bop(bop, a, b) => eval!(*a)?.handle_two_nums(
-            eval!(*b)?,
-            |a, b| a.bop(bop, b, context, int),
-            |a| |f| Expr::Bop(bop, f, Box::new(Expr::Literal(Value::Num(Box::new(a))))),
-            |a| |f| Expr::Bop(bop, Box::new(Expr::Literal(Value::Num(Box::new(a)))), f),
-            scope,
-        )?,
+        Expr::Bop(Bop::Mod, a, b) => {
+            let a = evaluate_ring_operands(*a, scope.clone(), context, int)?;
+            let modulus = evaluate_modulus(&a, *b, scope.clone(), context, int)?;
+            let ring = match &modulus {
+                Value::Num(n) => Modular::new(0.into(), (**n).clone(), int).ok(),
+                _ => None,
+            };
+            let Some(ring) = ring else {
+                return evaluate_bop(Bop::Mod, eval!(a)?, modulus, scope, context, int);
+            };
+            // integers are reduced after every step, so that e.g. `3^1000000 mod 7`
+            // doesn't need to compute the power in full
+            match evaluate_in_ring(&a, &ring, false, scope.as_ref(), context, int)? {
+                RingValue::Element(res, Some(Value::Num(n))) => {
+                    Value::Num(Box::new(res.value().with_base(n.base())))
+                }
+                RingValue::Element(res, None) => Value::Num(Box::new(res.value())),
+                res => evaluate_bop(Bop::Mod, res.into_value()?, modulus, scope, context, int)?,
+            }
+        }
+        Expr::Bop(bop, a, b) => evaluate_bop(bop, eval!(*a)?, eval!(*b)?, scope, context, int)?,
         Expr::Apply(a, b) | Expr::ApplyMul(a, b) => {
             if let (Expr::Ident(a), Expr::Ident(b)) = (&*a, &*b) {
                 let ident = format!("{a}_{b}");
@@ -392,14 +549,37 @@ pub(crate) fn evaluate<I: Interrupt>(
                     return Ok(val);
                 }
             }
-            eval!(*a)?.apply(*b, ApplyMulHandling::Both, scope, context, int)?
-        }
-        Expr::ApplyFunctionCall(a, b) => {
-            eval!(*a)?.apply(*b, ApplyMulHandling::OnlyApply, scope, context, int)?
+            evaluate_function(*a, scope.clone(), context, int)?.apply(
+                *b,
+                ApplyMulHandling::Both,
+                scope,
+                context,
+                int,
+            )?
         }
+        Expr::ApplyFunctionCall(a, b) => evaluate_function(*a, scope.clone(), context, int)?
+            .apply(*b, ApplyMulHandling::OnlyApply, scope, context, int)?,
         Expr::As(a, b) => evaluate_as(*a, *b, scope, context, int)?,
         Expr::Fn(a, b) => Value::Fn(a, b, scope),
-        Expr::Of(a, b) => eval!(*b)?.get_object_member(&a)?,
+        Expr::Of(a, b) => {
+            let object = eval!(*b)?;
+            if let Value::String(_) = object {
+                // e.g. `2 mol of "NaCl"`
+                let quantity = resolve_identifier(&a, scope, context, int)?.expect_num()?;
+                let molar_mass = crate::chemistry::molar_mass(object, context, int)?;
+                return Ok(Value::Num(Box::new(
+                    quantity.apply_molar_mass(molar_mass, int)?,
+                )));
+            }
+            match (object.substance_density(), object.get_object_member(&a)) {
+                // e.g. `cups of sugar`
+                (Some(density), Err(FendError::CouldNotFindKeyInObject)) => {
+                    let quantity = resolve_identifier(&a, scope, context, int)?.expect_num()?;
+                    Value::Num(Box::new(quantity.apply_density(density, int)?))
+                }
+                (_, res) => res?,
+            }
+        }
         Expr::Assign(a, b) => {
             let rhs = evaluate(*b, scope, context, int)?;
             context.variables.insert(a.to_string(), rhs.clone());
@@ -409,9 +589,328 @@ pub(crate) fn evaluate<I: Interrupt>(
             let _lhs = evaluate(*a, scope.clone(), context, int)?;
             evaluate(*b, scope, context, int)?
         }
+        Expr::Solve(a, b, c) => {
+            crate::numerical::solve(&a, *b, c.map(|c| *c), scope, context, int)?
+        }
+        Expr::Modular(a, b) => {
+            let a = evaluate_ring_operands(*a, scope.clone(), context, int)?;
+            let modulus = evaluate_modulus(&a, *b, scope.clone(), context, int)?;
+            let ring = Modular::new(0.into(), modulus.expect_num()?, int)?;
+            match evaluate_in_ring(&a, &ring, true, scope.as_ref(), context, int)? {
+                RingValue::Element(res, _) => Value::Modular(res),
+                // adding zero converts the value into the ring
+                RingValue::Other(value) => {
+                    Value::Modular(ring).modular_bop(Bop::Plus, value, int)?
+                }
+            }
+        }
+        Expr::List(items) => {
+            let mut values = Vec::with_capacity(items.len());
+            for item in items {
+                values.push(eval!(item)?);
+            }
+            Value::List(values)
+        }
+        Expr::UnitDefinition(a, b, c) => {
+            let definition = match c {
+                Some(c) => Some(eval!(*c)?),
+                None => None,
+            };
+            crate::units::define_custom_unit(a.as_str(), b, definition, context, int)?
+        }
+        Expr::Describe(a) => Value::String(
+            crate::units::describe_unit(a.as_str(), context, int)?
+                .to_string()
+                .into(),
+        ),
+        Expr::AtPixelDensity(a, b) => {
+            let density = eval!(*b)?.expect_num()?;
+            evaluate_at_pixel_density(*a, density, scope, context, int)?
+        }
     })
 }
 
+/// Evaluates `expr` with `px` defined using the given pixel density, which
+/// is either a plain number or a value like `326 ppi`
+fn evaluate_at_pixel_density<I: Interrupt>(
+    expr: Expr,
+    density: Number,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let pixels_per_inch = if density.is_unitless(int)? {
+        density
+    } else {
+        let dpi = crate::units::query_unit("dpi", context, int)?.expect_num()?;
+        density.convert_to(dpi, int)?.without_unit()
+    };
+    if pixels_per_inch.clone().try_as_f64(int)? <= 0.0 {
+        return Err(FendError::ExpectedPositiveValue("pixel density"));
+    }
+    let previous = std::mem::replace(&mut context.pixels_per_inch, pixels_per_inch);
+    let result = evaluate(expr, scope, context, int);
+    context.pixels_per_inch = previous;
+    result
+}
+
+fn evaluate_bop<I: Interrupt>(
+    bop: Bop,
+    a: Value,
+    b: Value,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if matches!(a, Value::Modular(_)) || matches!(b, Value::Modular(_)) {
+        return a.modular_bop(bop, b, int);
+    }
+    a.handle_two_nums(
+        b,
+        |a, b| a.bop(bop, b, context, int),
+        |a| |f| Expr::Bop(bop, f, Box::new(Expr::Literal(Value::Num(Box::new(a))))),
+        |a| |f| Expr::Bop(bop, Box::new(Expr::Literal(Value::Num(Box::new(a)))), f),
+        scope,
+    )
+}
+
+fn evaluate_neg<I: Interrupt>(
+    value: Value,
+    scope: Option<Arc<Scope>>,
+    int: &I,
+) -> Result<Value, FendError> {
+    match value {
+        Value::Modular(m) => Ok(Value::Modular(m.neg(int)?)),
+        x => x.handle_num(|x| Ok(-x), Expr::UnaryMinus, scope),
+    }
+}
+
+/// Powers with larger exponents are only computed within the ring
+const MAX_EXACT_RING_EXPONENT: usize = 1000;
+
+/// Part of an expression that has been evaluated in a ring of integers
+enum RingValue {
+    /// An integer, reduced into the ring. Its actual value is kept as well,
+    /// unless it is too large to compute (e.g. `3^1000000`).
+    Element(Modular, Option<Value>),
+    /// Any other value, e.g. a fraction or a number with a unit
+    Other(Value),
+}
+
+impl RingValue {
+    fn new<I: Interrupt>(value: Value, ring: &Modular, int: &I) -> Self {
+        let element = match &value {
+            Value::Num(n) => ring.with_value((**n).clone(), int).ok(),
+            Value::Modular(m) if ring.has_same_modulus(m) => Some(m.clone()),
+            _ => None,
+        };
+        match element {
+            Some(element) => Self::Element(element, Some(value)),
+            None => Self::Other(value),
+        }
+    }
+
+    fn into_value(self) -> Result<Value, FendError> {
+        match self {
+            Self::Element(_, Some(value)) | Self::Other(value) => Ok(value),
+            Self::Element(_, None) => Err(FendError::ExponentTooLarge),
+        }
+    }
+}
+
+/// Evaluates the parts of `expr` that `evaluate_in_ring` doesn't combine
+/// itself, replacing them with their values. This evaluates the left-hand
+/// side of e.g. `a mod b` before the modulus, without computing large
+/// powers in full.
+fn evaluate_ring_operands<I: Interrupt>(
+    expr: Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Expr, FendError> {
+    macro_rules! operands {
+        ($e:expr) => {
+            Box::new(evaluate_ring_operands(*$e, scope.clone(), context, int)?)
+        };
+    }
+    Ok(match expr {
+        Expr::Parens(x) => Expr::Parens(operands!(x)),
+        Expr::UnaryPlus(x) => Expr::UnaryPlus(operands!(x)),
+        Expr::UnaryMinus(x) => Expr::UnaryMinus(operands!(x)),
+        Expr::Bop(
+            bop @ (Bop::Plus | Bop::ImplicitPlus | Bop::Minus | Bop::Mul | Bop::Div),
+            a,
+            b,
+        ) => {
+            let a = operands!(a);
+            Expr::Bop(bop, a, operands!(b))
+        }
+        Expr::Bop(Bop::Pow, a, b) => {
+            let base = operands!(a);
+            let exponent = evaluate(*b, scope, context, int)?;
+            Expr::Bop(Bop::Pow, base, Box::new(Expr::Literal(exponent)))
+        }
+        expr => Expr::Literal(evaluate(expr, scope, context, int)?),
+    })
+}
+
+/// Evaluates the modulus `b` of e.g. `a mod b`, where `a` has been evaluated
+/// with `evaluate_ring_operands`. If the modulus is invalid, errors from
+/// combining the left-hand side are reported first.
+fn evaluate_modulus<I: Interrupt>(
+    a: &Expr,
+    b: Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    match evaluate(b, scope.clone(), context, int) {
+        Ok(modulus) => Ok(modulus.without_note()),
+        Err(FendError::Interrupted) => Err(FendError::Interrupted),
+        Err(e) => {
+            evaluate(a.clone(), scope, context, int)?;
+            Err(e)
+        }
+    }
+}
+
+/// Evaluates `expr`, reducing integers into `ring` after every step so
+/// that large powers never need to be computed in full. Every part of the
+/// expression is evaluated exactly once, and parts that aren't integers
+/// are evaluated normally.
+fn evaluate_in_ring<I: Interrupt>(
+    expr: &Expr,
+    ring: &Modular,
+    allow_division: bool,
+    scope: Option<&Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<RingValue, FendError> {
+    macro_rules! eval_in_ring {
+        ($e:expr) => {
+            evaluate_in_ring($e, ring, allow_division, scope, context, int)?
+        };
+    }
+    Ok(match expr {
+        Expr::Parens(x) => eval_in_ring!(x),
+        Expr::UnaryPlus(x) => match eval_in_ring!(x) {
+            RingValue::Other(value) => {
+                RingValue::Other(value.handle_num(Ok, Expr::UnaryPlus, scope.cloned())?)
+            }
+            res @ RingValue::Element(_, _) => res,
+        },
+        Expr::UnaryMinus(x) => match eval_in_ring!(x) {
+            RingValue::Element(res, value) => RingValue::Element(
+                res.neg(int)?,
+                value
+                    .map(|value| evaluate_neg(value, scope.cloned(), int))
+                    .transpose()?,
+            ),
+            RingValue::Other(value) => RingValue::Other(evaluate_neg(value, scope.cloned(), int)?),
+        },
+        Expr::Bop(
+            bop @ (Bop::Plus | Bop::ImplicitPlus | Bop::Minus | Bop::Mul | Bop::Div),
+            a,
+            b,
+        ) => {
+            let a = eval_in_ring!(a);
+            let b = eval_in_ring!(b);
+            evaluate_bop_in_ring(*bop, a, b, ring, allow_division, scope, context, int)?
+        }
+        Expr::Bop(Bop::Pow, a, b) => {
+            let base = eval_in_ring!(a);
+            let exponent = evaluate((**b).clone(), scope.cloned(), context, int)?;
+            evaluate_pow_in_ring(base, exponent, ring, allow_division, scope, context, int)?
+        }
+        _ => RingValue::new(
+            evaluate(expr.clone(), scope.cloned(), context, int)?,
+            ring,
+            int,
+        ),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn evaluate_bop_in_ring<I: Interrupt>(
+    bop: Bop,
+    a: RingValue,
+    b: RingValue,
+    ring: &Modular,
+    allow_division: bool,
+    scope: Option<&Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<RingValue, FendError> {
+    let combine = |a, b, context: &mut crate::Context| match bop {
+        Bop::Plus => evaluate_add(a, b, scope.cloned(), int),
+        _ => evaluate_bop(bop, a, b, scope.cloned(), context, int),
+    };
+    if let (RingValue::Element(x, x_value), RingValue::Element(y, y_value)) = (&a, &b) {
+        let res = match bop {
+            Bop::Plus | Bop::ImplicitPlus => Some(x.add(y, int)?),
+            Bop::Minus => Some(x.sub(y, int)?),
+            Bop::Mul => Some(x.mul(y, int)?),
+            Bop::Div if allow_division => Some(x.div(y, int)?),
+            _ => None,
+        };
+        if let Some(res) = res {
+            let value = match (x_value, y_value) {
+                (Some(x), Some(y)) => Some(combine(x.clone(), y.clone(), context)?),
+                _ => None,
+            };
+            return Ok(RingValue::Element(res, value));
+        }
+    }
+    let value = combine(a.into_value()?, b.into_value()?, context)?;
+    Ok(RingValue::new(value, ring, int))
+}
+
+fn evaluate_pow_in_ring<I: Interrupt>(
+    base: RingValue,
+    exponent: Value,
+    ring: &Modular,
+    allow_division: bool,
+    scope: Option<&Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<RingValue, FendError> {
+    if let (RingValue::Element(res, value), Value::Num(n)) = (&base, &exponent) {
+        if let Ok((negative, _)) = n.clone().try_as_integer(int) {
+            if !negative || allow_division {
+                let is_small = n
+                    .clone()
+                    .abs(int)?
+                    .try_as_usize(int)
+                    .is_ok_and(|n| n <= MAX_EXACT_RING_EXPONENT);
+                let value = match value {
+                    Some(value) if is_small => Some(evaluate_bop(
+                        Bop::Pow,
+                        value.clone(),
+                        exponent.clone(),
+                        scope.cloned(),
+                        context,
+                        int,
+                    )?),
+                    _ => None,
+                };
+                return Ok(RingValue::Element(
+                    res.clone().pow((**n).clone(), int)?,
+                    value,
+                ));
+            }
+        }
+    }
+    let value = evaluate_bop(
+        Bop::Pow,
+        base.into_value()?,
+        exponent,
+        scope.cloned(),
+        context,
+        int,
+    )?;
+    Ok(RingValue::new(value, ring, int))
+}
+
 fn evaluate_add<I: Interrupt>(
     a: Value,
     b: Value,
@@ -449,11 +948,48 @@ fn evaluate_add<I: Interrupt>(
             )),
             scope,
         ),
+        (a @ Value::Modular(_), b) | (a, b @ Value::Modular(_)) => {
+            a.modular_bop(Bop::Plus, b, int)?
+        }
         (Value::Date(d), b) => d.add(b)?,
         _ => return Err(FendError::ExpectedANumber),
     })
 }
 
+/// Converts a number to the unit of `target`, inverting it first if only its
+/// reciprocal has a matching unit, e.g. for `2 s to Hz`
+fn convert_num_to<I: Interrupt>(num: Number, target: Number, int: &I) -> Result<Value, FendError> {
+    Ok(if num.converts_to_reciprocal(&target, int)? {
+        Value::NumWithNote(
+            Box::new(num.convert_reciprocal_to(target, int)?),
+            "converted via reciprocal".into(),
+        )
+    } else {
+        Value::Num(Box::new(num.convert_to(target, int)?))
+    })
+}
+
+/// Evaluates `a` and splits it into the given units, e.g. for
+/// `1.75 m to ft, in`
+fn evaluate_as_compound<I: Interrupt>(
+    a: Expr,
+    units: Vec<Value>,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if units.is_empty() {
+        return Err(FendError::ExpectedAList);
+    }
+    let units = units
+        .into_iter()
+        .map(Value::expect_num)
+        .collect::<Result<Vec<_>, _>>()?;
+    let num = evaluate(a, scope, context, int)?.expect_num()?;
+    let parts = num.clone().convert_to_compound(units, int)?;
+    Ok(Value::Compound(Box::new(num), parts))
+}
+
 fn evaluate_as<I: Interrupt>(
     a: Expr,
     b: Expr,
@@ -482,6 +1018,31 @@ fn evaluate_as<I: Interrupt>(
                         .into(),
                 ));
             }
+            "simplest" => {
+                return Ok(Value::Num(Box::new(
+                    evaluate(a, scope, context, int)?
+                        .expect_num()?
+                        .into_simplest_unit(context, int)?,
+                )));
+            }
+            "auto_prefix" => {
+                let num = evaluate(a, scope, context, int)?.expect_num()?;
+                return Ok(Value::Num(Box::new(crate::units::auto_prefix(
+                    num, context, int,
+                )?)));
+            }
+            "polar" | "cartesian" => {
+                let complex_format = if ident.as_str() == "polar" {
+                    ComplexFormat::Polar
+                } else {
+                    ComplexFormat::Cartesian
+                };
+                return Ok(Value::Num(Box::new(
+                    evaluate(a, scope, context, int)?
+                        .expect_num()?
+                        .with_complex_format(complex_format),
+                )));
+            }
             "codepoint" => {
                 let a = evaluate(a, scope, context, int)?;
                 if let Value::String(s) = a {
@@ -503,12 +1064,17 @@ fn evaluate_as<I: Interrupt>(
             _ => (),
         }
     }
-    Ok(match evaluate(b, scope.clone(), context, int)? {
-        Value::Num(b) => Value::Num(Box::new(
-            evaluate(a, scope, context, int)?
-                .expect_num()?
-                .convert_to(*b, int)?,
-        )),
+    let target = match split_per_quantity_target(&b) {
+        Some((numerator, amount, unit)) => {
+            evaluate_per_quantity_target(numerator, amount, unit, scope.clone(), context, int)?
+        }
+        None => evaluate(b, scope.clone(), context, int)?,
+    };
+    Ok(match target {
+        Value::Num(b) => {
+            let a = evaluate(a, scope, context, int)?.expect_num()?;
+            convert_num_to(a, *b, int)?
+        }
         Value::Format(fmt) => Value::Num(Box::new(
             evaluate(a, scope, context, int)?
                 .expect_num()?
@@ -525,12 +1091,100 @@ fn evaluate_as<I: Interrupt>(
                 .expect_num()?
                 .with_base(base),
         )),
+        Value::List(units) => evaluate_as_compound(a, units, scope, context, int)?,
         other => {
             return Err(FendError::CannotConvertValueTo(other.type_name()));
         }
     })
 }
 
+// Conversion targets like `L/100km` (parsed as `(L/100) km`) or `L/(100 km)`
+// divide by a quantity rather than a unit. Returns the numerator, the amount
+// and the unit of that quantity.
+fn split_per_quantity_target(b: &Expr) -> Option<(&Expr, &Expr, &Expr)> {
+    match b {
+        Expr::Apply(lhs, unit) | Expr::ApplyMul(lhs, unit) => match lhs.as_ref() {
+            Expr::Bop(Bop::Div, numerator, amount)
+                if matches!(amount.as_ref(), Expr::Literal(Value::Num(_))) =>
+            {
+                Some((numerator, amount, unit))
+            }
+            _ => None,
+        },
+        Expr::Bop(Bop::Div, numerator, denominator) => match denominator.as_ref() {
+            Expr::Parens(inner) => match inner.as_ref() {
+                Expr::Apply(amount, unit) | Expr::ApplyMul(amount, unit)
+                    if matches!(amount.as_ref(), Expr::Literal(Value::Num(_))) =>
+                {
+                    Some((numerator, amount, unit))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Evaluates a target like `L/100km`, turning `100 km` into a unit so that
+// results are shown as e.g. `7.84 L / 100 km`
+fn evaluate_per_quantity_target<I: Interrupt>(
+    numerator: &Expr,
+    amount: &Expr,
+    unit: &Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let name = format!(
+        "{} {}",
+        amount.format(context, int)?,
+        unit.format(context, int)?
+    );
+    let quantity = Expr::ApplyMul(Box::new(amount.clone()), Box::new(unit.clone()));
+    let quantity = evaluate(quantity, scope.clone(), context, int)?.expect_num()?;
+    let per_unit = Number::create_unit_value_from_value(
+        &quantity,
+        Cow::Borrowed(""),
+        Cow::Owned(name.clone()),
+        Cow::Owned(name),
+        int,
+    )?;
+    Ok(Value::Num(Box::new(
+        evaluate(numerator.clone(), scope, context, int)?
+            .expect_num()?
+            .div(per_unit, int)?,
+    )))
+}
+
+/// Evaluates an expression that is applied to an argument. `pv` and `fv` only
+/// refer to the financial functions here, since elsewhere (e.g. in `1 pv`)
+/// they are picovolts and femtovolts.
+fn evaluate_function<I: Interrupt>(
+    f: Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if let Expr::Ident(ident) = &f {
+        let function = match ident.as_str() {
+            "pv" => Some(BuiltInFunction::Pv),
+            "fv" => Some(BuiltInFunction::Fv),
+            _ => None,
+        };
+        if let Some(function) = function {
+            let is_variable = match &scope {
+                Some(scope) => scope.get(ident, context, int)?.is_some(),
+                None => false,
+            } || context.variables.contains_key(ident.as_str());
+            if !is_variable {
+                return Ok(Value::BuiltInFunction(function));
+            }
+        }
+    }
+    evaluate(f, scope, context, int).map(Value::without_note)
+}
+
 pub(crate) fn resolve_identifier<I: Interrupt>(
     ident: &Ident,
     scope: Option<Arc<Scope>>,
@@ -562,7 +1216,17 @@ pub(crate) fn resolve_identifier<I: Interrupt>(
         "sqrt" => evaluate_to_value("x: x^(1/2)", scope, context, int)?,
         "cbrt" => evaluate_to_value("x: x^(1/3)", scope, context, int)?,
         "conjugate" => Value::BuiltInFunction(BuiltInFunction::Conjugate),
+        "arg" => Value::BuiltInFunction(BuiltInFunction::Arg),
         "abs" => Value::BuiltInFunction(BuiltInFunction::Abs),
+        "integrate" => Value::BuiltInFunction(BuiltInFunction::Integrate),
+        "derivative" => Value::BuiltInFunction(BuiltInFunction::Derivative),
+        "sum" => Value::BuiltInFunction(BuiltInFunction::Sum),
+        "product" => Value::BuiltInFunction(BuiltInFunction::Product),
+        "pmt" => Value::BuiltInFunction(BuiltInFunction::Pmt),
+        "compound" => Value::BuiltInFunction(BuiltInFunction::Compound),
+        "npv" => Value::BuiltInFunction(BuiltInFunction::Npv),
+        "irr" => Value::BuiltInFunction(BuiltInFunction::Irr),
+        "molar_mass" => Value::BuiltInFunction(BuiltInFunction::MolarMass),
         "sin" => Value::BuiltInFunction(BuiltInFunction::Sin),
         "cos" => Value::BuiltInFunction(BuiltInFunction::Cos),
         "tan" => Value::BuiltInFunction(BuiltInFunction::Tan),
@@ -599,17 +1263,46 @@ pub(crate) fn resolve_identifier<I: Interrupt>(
         "version" => Value::String(crate::get_version_as_str().into()),
         "square" => evaluate_to_value("x: x^2", scope, context, int)?,
         "cubic" => evaluate_to_value("x: x^3", scope, context, int)?,
-        "earth" => Value::Object(vec![
-            ("axial_tilt".into(), eval_box!("23.4392811 degrees")),
-            ("eccentricity".into(), eval_box!("0.0167086")),
-            ("escape_velocity".into(), eval_box!("11.186 km/s")),
-            ("gravity".into(), eval_box!("9.80665 m/s^2")),
-            ("mass".into(), eval_box!("5.97237e24 kg")),
-            ("volume".into(), eval_box!("1.08321e12 km^3")),
-        ]),
         "today" => Value::Date(crate::date::Date::today(context)?),
         "tomorrow" => Value::Date(crate::date::Date::today(context)?.next()),
         "yesterday" => Value::Date(crate::date::Date::today(context)?.prev()),
-        _ => return crate::units::query_unit(ident.as_str(), context, int),
+        name => {
+            if let Some(properties) = crate::astronomy::query_body(name) {
+                let mut object = Vec::with_capacity(properties.len());
+                for &(key, definition) in properties {
+                    object.push((key.into(), eval_box!(definition)));
+                }
+                return Ok(Value::Object(object));
+            }
+            if let Some(density) = crate::units::query_substance_density(name) {
+                let density = evaluate_to_value(density, scope, context, int)?.expect_num()?;
+                return Ok(Value::Substance(Box::new(density)));
+            }
+            if let Some(member) = resolve_object_member(name, scope, context, int)? {
+                return Ok(member);
+            }
+            return crate::units::query_unit(name, context, int);
+        }
     })
 }
+
+/// Resolves identifiers like `mars.radius`, returning `None` if the part
+/// before the dot is not an object
+fn resolve_object_member<I: Interrupt>(
+    ident: &str,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<Value>, FendError> {
+    let (object, key) = match ident.split_once('.') {
+        Some((object, key)) if !object.is_empty() && !key.is_empty() => (object, key),
+        _ => return Ok(None),
+    };
+    match resolve_identifier(&Ident::new_string(object.to_string()), scope, context, int) {
+        Ok(object @ (Value::Object(_) | Value::Date(_) | Value::Substance(_))) => Ok(Some(
+            object.get_object_member(&Ident::new_string(key.to_string()))?,
+        )),
+        Err(FendError::Interrupted) => Err(FendError::Interrupted),
+        _ => Ok(None),
+    }
+}
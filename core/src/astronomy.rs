@@ -0,0 +1,242 @@
+type Properties = &'static [(&'static str, &'static str)];
+
+// Properties of the Sun, the Moon, the planets and the dwarf planets, mostly
+// from NASA's planetary fact sheets. Radii are mean radii, and gravity is
+// measured at the surface (or at the equator for the gas giants).
+const BODIES: &[(&str, Properties)] = &[
+    (
+        "sun",
+        &[
+            ("axial_tilt", "7.25 degrees"),
+            ("density", "1.408 g/cm^3"),
+            ("escape_velocity", "617.7 km/s"),
+            ("gravity", "274 m/s^2"),
+            ("mass", "1.9885e30 kg"),
+            ("radius", "695700 km"),
+            ("rotation_period", "25.05 days"),
+            ("volume", "1.412e18 km^3"),
+        ],
+    ),
+    (
+        "moon",
+        &[
+            ("axial_tilt", "6.687 degrees"),
+            ("density", "3.344 g/cm^3"),
+            ("eccentricity", "0.0549"),
+            ("escape_velocity", "2.38 km/s"),
+            ("gravity", "1.62 m/s^2"),
+            ("mass", "7.342e22 kg"),
+            ("orbital_period", "27.321661 days"),
+            ("radius", "1737.4 km"),
+            ("rotation_period", "27.321661 days"),
+            ("semi_major_axis", "384399 km"),
+            ("volume", "2.1958e10 km^3"),
+        ],
+    ),
+    (
+        "mercury",
+        &[
+            ("axial_tilt", "0.034 degrees"),
+            ("density", "5.427 g/cm^3"),
+            ("eccentricity", "0.20563"),
+            ("escape_velocity", "4.25 km/s"),
+            ("gravity", "3.7 m/s^2"),
+            ("mass", "3.3011e23 kg"),
+            ("orbital_period", "87.9691 days"),
+            ("radius", "2439.7 km"),
+            ("rotation_period", "58.646 days"),
+            ("semi_major_axis", "0.387098 au"),
+            ("volume", "6.083e10 km^3"),
+        ],
+    ),
+    (
+        "venus",
+        &[
+            ("axial_tilt", "177.36 degrees"),
+            ("density", "5.243 g/cm^3"),
+            ("eccentricity", "0.006772"),
+            ("escape_velocity", "10.36 km/s"),
+            ("gravity", "8.87 m/s^2"),
+            ("mass", "4.8675e24 kg"),
+            ("orbital_period", "224.701 days"),
+            ("radius", "6051.8 km"),
+            ("rotation_period", "243.0226 days"),
+            ("semi_major_axis", "0.723332 au"),
+            ("volume", "9.2843e11 km^3"),
+        ],
+    ),
+    (
+        "earth",
+        &[
+            ("axial_tilt", "23.4392811 degrees"),
+            ("density", "5.514 g/cm^3"),
+            ("eccentricity", "0.0167086"),
+            ("escape_velocity", "11.186 km/s"),
+            ("gravity", "9.80665 m/s^2"),
+            ("mass", "5.97237e24 kg"),
+            ("orbital_period", "365.256363004 days"),
+            ("radius", "6371 km"),
+            ("rotation_period", "0.99726968 days"),
+            ("semi_major_axis", "1.00000102 au"),
+            ("volume", "1.08321e12 km^3"),
+        ],
+    ),
+    (
+        "mars",
+        &[
+            ("axial_tilt", "25.19 degrees"),
+            ("density", "3.9335 g/cm^3"),
+            ("eccentricity", "0.0934"),
+            ("escape_velocity", "5.027 km/s"),
+            ("gravity", "3.72076 m/s^2"),
+            ("mass", "6.4171e23 kg"),
+            ("orbital_period", "686.98 days"),
+            ("radius", "3389.5 km"),
+            ("rotation_period", "1.02595676 days"),
+            ("semi_major_axis", "1.523679 au"),
+            ("volume", "1.6318e11 km^3"),
+        ],
+    ),
+    (
+        "jupiter",
+        &[
+            ("axial_tilt", "3.13 degrees"),
+            ("density", "1.326 g/cm^3"),
+            ("eccentricity", "0.0489"),
+            ("escape_velocity", "59.5 km/s"),
+            ("gravity", "24.79 m/s^2"),
+            ("mass", "1.8982e27 kg"),
+            ("orbital_period", "4332.59 days"),
+            ("radius", "69911 km"),
+            ("rotation_period", "9.925 hours"),
+            ("semi_major_axis", "5.2038 au"),
+            ("volume", "1.4313e15 km^3"),
+        ],
+    ),
+    (
+        "saturn",
+        &[
+            ("axial_tilt", "26.73 degrees"),
+            ("density", "0.687 g/cm^3"),
+            ("eccentricity", "0.0565"),
+            ("escape_velocity", "35.5 km/s"),
+            ("gravity", "10.44 m/s^2"),
+            ("mass", "5.6834e26 kg"),
+            ("orbital_period", "10759.22 days"),
+            ("radius", "58232 km"),
+            ("rotation_period", "10.656 hours"),
+            ("semi_major_axis", "9.5826 au"),
+            ("volume", "8.2713e14 km^3"),
+        ],
+    ),
+    (
+        "uranus",
+        &[
+            ("axial_tilt", "97.77 degrees"),
+            ("density", "1.27 g/cm^3"),
+            ("eccentricity", "0.04717"),
+            ("escape_velocity", "21.3 km/s"),
+            ("gravity", "8.69 m/s^2"),
+            ("mass", "8.681e25 kg"),
+            ("orbital_period", "30688.5 days"),
+            ("radius", "25362 km"),
+            ("rotation_period", "17.24 hours"),
+            ("semi_major_axis", "19.19126 au"),
+            ("volume", "6.833e13 km^3"),
+        ],
+    ),
+    (
+        "neptune",
+        &[
+            ("axial_tilt", "28.32 degrees"),
+            ("density", "1.638 g/cm^3"),
+            ("eccentricity", "0.008678"),
+            ("escape_velocity", "23.5 km/s"),
+            ("gravity", "11.15 m/s^2"),
+            ("mass", "1.02413e26 kg"),
+            ("orbital_period", "60195 days"),
+            ("radius", "24622 km"),
+            ("rotation_period", "16.11 hours"),
+            ("semi_major_axis", "30.07 au"),
+            ("volume", "6.254e13 km^3"),
+        ],
+    ),
+    (
+        "pluto",
+        &[
+            ("axial_tilt", "122.53 degrees"),
+            ("density", "1.854 g/cm^3"),
+            ("eccentricity", "0.2488"),
+            ("escape_velocity", "1.21 km/s"),
+            ("gravity", "0.62 m/s^2"),
+            ("mass", "1.303e22 kg"),
+            ("orbital_period", "90560 days"),
+            ("radius", "1188.3 km"),
+            ("rotation_period", "6.38723 days"),
+            ("semi_major_axis", "39.482 au"),
+        ],
+    ),
+    (
+        "ceres",
+        &[
+            ("axial_tilt", "4 degrees"),
+            ("density", "2.162 g/cm^3"),
+            ("eccentricity", "0.0785"),
+            ("escape_velocity", "0.51 km/s"),
+            ("gravity", "0.28 m/s^2"),
+            ("mass", "9.3839e20 kg"),
+            ("orbital_period", "1680 days"),
+            ("radius", "469.7 km"),
+            ("rotation_period", "9.07417 hours"),
+            ("semi_major_axis", "2.7675 au"),
+        ],
+    ),
+    (
+        "eris",
+        &[
+            ("density", "2.43 g/cm^3"),
+            ("eccentricity", "0.43607"),
+            ("escape_velocity", "1.38 km/s"),
+            ("gravity", "0.82 m/s^2"),
+            ("mass", "1.6466e22 kg"),
+            ("orbital_period", "203830 days"),
+            ("radius", "1163 km"),
+            ("rotation_period", "15.786 days"),
+            ("semi_major_axis", "67.864 au"),
+        ],
+    ),
+    (
+        "haumea",
+        &[
+            ("density", "2.018 g/cm^3"),
+            ("eccentricity", "0.19642"),
+            ("mass", "4.006e21 kg"),
+            ("orbital_period", "103410 days"),
+            ("radius", "780 km"),
+            ("rotation_period", "3.9155 hours"),
+            ("semi_major_axis", "43.218 au"),
+        ],
+    ),
+    (
+        "makemake",
+        &[
+            ("eccentricity", "0.16126"),
+            ("escape_velocity", "0.91 km/s"),
+            ("gravity", "0.57 m/s^2"),
+            ("mass", "3.1e21 kg"),
+            ("orbital_period", "111845 days"),
+            ("radius", "715 km"),
+            ("rotation_period", "22.83 hours"),
+            ("semi_major_axis", "45.43 au"),
+        ],
+    ),
+];
+
+/// Returns the properties of an astronomical body like `mars`, as pairs of
+/// property names and definitions
+pub(crate) fn query_body(name: &str) -> Option<Properties> {
+    BODIES
+        .iter()
+        .find(|(body, _)| *body == name)
+        .map(|(_, properties)| *properties)
+}
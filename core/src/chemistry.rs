@@ -0,0 +1,225 @@
+use crate::error::{FendError, Interrupt};
+use crate::eval::evaluate_to_value;
+use crate::num::Number;
+use crate::value::Value;
+
+// Symbols, names and standard atomic weights (IUPAC 2007 values) of all
+// elements, ordered by atomic number. Elements without a standard atomic
+// weight use the mass number of their longest-lived isotope.
+const ELEMENTS: &[(&str, &str, &str)] = &[
+    ("H", "hydrogen", "1.00794"),
+    ("He", "helium", "4.002602"),
+    ("Li", "lithium", "6.941"),
+    ("Be", "beryllium", "9.012182"),
+    ("B", "boron", "10.811"),
+    ("C", "carbon", "12.0107"),
+    ("N", "nitrogen", "14.0067"),
+    ("O", "oxygen", "15.9994"),
+    ("F", "fluorine", "18.9984032"),
+    ("Ne", "neon", "20.1797"),
+    ("Na", "sodium", "22.98976928"),
+    ("Mg", "magnesium", "24.305"),
+    ("Al", "aluminium", "26.9815386"),
+    ("Si", "silicon", "28.0855"),
+    ("P", "phosphorus", "30.973762"),
+    ("S", "sulfur", "32.065"),
+    ("Cl", "chlorine", "35.453"),
+    ("Ar", "argon", "39.948"),
+    ("K", "potassium", "39.0983"),
+    ("Ca", "calcium", "40.078"),
+    ("Sc", "scandium", "44.955912"),
+    ("Ti", "titanium", "47.867"),
+    ("V", "vanadium", "50.9415"),
+    ("Cr", "chromium", "51.9961"),
+    ("Mn", "manganese", "54.938045"),
+    ("Fe", "iron", "55.845"),
+    ("Co", "cobalt", "58.933195"),
+    ("Ni", "nickel", "58.6934"),
+    ("Cu", "copper", "63.546"),
+    ("Zn", "zinc", "65.38"),
+    ("Ga", "gallium", "69.723"),
+    ("Ge", "germanium", "72.64"),
+    ("As", "arsenic", "74.9216"),
+    ("Se", "selenium", "78.96"),
+    ("Br", "bromine", "79.904"),
+    ("Kr", "krypton", "83.798"),
+    ("Rb", "rubidium", "85.4678"),
+    ("Sr", "strontium", "87.62"),
+    ("Y", "yttrium", "88.90585"),
+    ("Zr", "zirconium", "91.224"),
+    ("Nb", "niobium", "92.90638"),
+    ("Mo", "molybdenum", "95.96"),
+    ("Tc", "technetium", "97"),
+    ("Ru", "ruthenium", "101.07"),
+    ("Rh", "rhodium", "102.9055"),
+    ("Pd", "palladium", "106.42"),
+    ("Ag", "silver", "107.8682"),
+    ("Cd", "cadmium", "112.411"),
+    ("In", "indium", "114.818"),
+    ("Sn", "tin", "118.71"),
+    ("Sb", "antimony", "121.76"),
+    ("Te", "tellurium", "127.6"),
+    ("I", "iodine", "126.90447"),
+    ("Xe", "xenon", "131.293"),
+    ("Cs", "caesium", "132.9054519"),
+    ("Ba", "barium", "137.327"),
+    ("La", "lanthanum", "138.90547"),
+    ("Ce", "cerium", "140.116"),
+    ("Pr", "praseodymium", "140.90765"),
+    ("Nd", "neodymium", "144.242"),
+    ("Pm", "promethium", "145"),
+    ("Sm", "samarium", "150.36"),
+    ("Eu", "europium", "151.964"),
+    ("Gd", "gadolinium", "157.25"),
+    ("Tb", "terbium", "158.92535"),
+    ("Dy", "dysprosium", "162.5"),
+    ("Ho", "holmium", "164.93032"),
+    ("Er", "erbium", "167.259"),
+    ("Tm", "thulium", "168.93421"),
+    ("Yb", "ytterbium", "173.054"),
+    ("Lu", "lutetium", "174.9668"),
+    ("Hf", "hafnium", "178.49"),
+    ("Ta", "tantalum", "180.94788"),
+    ("W", "tungsten", "183.84"),
+    ("Re", "rhenium", "186.207"),
+    ("Os", "osmium", "190.23"),
+    ("Ir", "iridium", "192.217"),
+    ("Pt", "platinum", "195.084"),
+    ("Au", "gold", "196.966569"),
+    ("Hg", "mercury", "200.59"),
+    ("Tl", "thallium", "204.3833"),
+    ("Pb", "lead", "207.2"),
+    ("Bi", "bismuth", "208.9804"),
+    ("Po", "polonium", "209"),
+    ("At", "astatine", "210"),
+    ("Rn", "radon", "222"),
+    ("Fr", "francium", "223"),
+    ("Ra", "radium", "226"),
+    ("Ac", "actinium", "227"),
+    ("Th", "thorium", "232.03806"),
+    ("Pa", "protactinium", "231.03588"),
+    ("U", "uranium", "238.02891"),
+    ("Np", "neptunium", "237"),
+    ("Pu", "plutonium", "244"),
+    ("Am", "americium", "243"),
+    ("Cm", "curium", "247"),
+    ("Bk", "berkelium", "247"),
+    ("Cf", "californium", "251"),
+    ("Es", "einsteinium", "252"),
+    ("Fm", "fermium", "257"),
+    ("Md", "mendelevium", "258"),
+    ("No", "nobelium", "259"),
+    ("Lr", "lawrencium", "266"),
+    ("Rf", "rutherfordium", "267"),
+    ("Db", "dubnium", "268"),
+    ("Sg", "seaborgium", "269"),
+    ("Bh", "bohrium", "270"),
+    ("Hs", "hassium", "269"),
+    ("Mt", "meitnerium", "278"),
+    ("Ds", "darmstadtium", "281"),
+    ("Rg", "roentgenium", "282"),
+    ("Cn", "copernicium", "285"),
+    ("Nh", "nihonium", "286"),
+    ("Fl", "flerovium", "289"),
+    ("Mc", "moscovium", "290"),
+    ("Lv", "livermorium", "293"),
+    ("Ts", "tennessine", "294"),
+    ("Og", "oganesson", "294"),
+];
+
+fn atomic_weight<I: Interrupt>(element: &str, int: &I) -> Result<Number, FendError> {
+    let (_, _, weight) = ELEMENTS
+        .iter()
+        .find(|(symbol, _, _)| *symbol == element)
+        .ok_or_else(|| FendError::UnknownElement(element.to_string()))?;
+    Ok(crate::lexer::parse_number(weight, int)?.0)
+}
+
+struct FormulaParser<'a> {
+    formula: &'a str,
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl FormulaParser<'_> {
+    fn invalid(&self) -> FendError {
+        FendError::InvalidChemicalFormula(self.formula.to_string())
+    }
+
+    fn parse_count(&mut self) -> Result<u64, FendError> {
+        let mut digits = String::new();
+        while let Some(ch) = self.chars.next_if(char::is_ascii_digit) {
+            digits.push(ch);
+        }
+        if digits.is_empty() {
+            return Ok(1);
+        }
+        digits.parse().map_err(|_| self.invalid())
+    }
+
+    /// Parses elements and groups like `(OH)2` until the given closing
+    /// bracket, returning their total weight
+    fn parse_sequence<I: Interrupt>(
+        &mut self,
+        closing: Option<char>,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        let mut total = Number::from(0);
+        let mut empty = true;
+        loop {
+            let weight = match self.chars.next() {
+                None if closing.is_none() && !empty => return Ok(total),
+                Some(ch) if Some(ch) == closing && !empty => return Ok(total),
+                Some('(') => self.parse_sequence(Some(')'), int)?,
+                Some('[') => self.parse_sequence(Some(']'), int)?,
+                Some(ch) if ch.is_ascii_uppercase() => {
+                    let mut symbol = ch.to_string();
+                    while let Some(ch) = self.chars.next_if(char::is_ascii_lowercase) {
+                        symbol.push(ch);
+                    }
+                    atomic_weight(&symbol, int)?
+                }
+                _ => return Err(self.invalid()),
+            };
+            let count = self.parse_count()?;
+            total = total.add(weight.mul(Number::from(count), int)?, int)?;
+            empty = false;
+        }
+    }
+}
+
+/// Returns the total atomic weight of a formula like `H2SO4`, `Ca(OH)2`
+/// or `CuSO4·5H2O`, or of an element name like `iron`
+fn formula_weight<I: Interrupt>(formula: &str, int: &I) -> Result<Number, FendError> {
+    if let Some((symbol, _, _)) = ELEMENTS
+        .iter()
+        .find(|(_, name, _)| name.eq_ignore_ascii_case(formula))
+    {
+        return atomic_weight(symbol, int);
+    }
+    let mut total = Number::from(0);
+    // parts of hydrates and other adducts, e.g. `5H2O` in `CuSO4·5H2O`
+    for part in formula.split(['\u{b7}', '.', '*']) {
+        let mut parser = FormulaParser {
+            formula,
+            chars: part.trim().chars().peekable(),
+        };
+        let count = parser.parse_count()?;
+        let weight = parser.parse_sequence(None, int)?;
+        total = total.add(weight.mul(Number::from(count), int)?, int)?;
+    }
+    Ok(total)
+}
+
+/// Returns the molar mass of a chemical formula, e.g. `molar_mass "H2O"`
+pub(crate) fn molar_mass<I: Interrupt>(
+    formula: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let Value::String(formula) = formula else {
+        return Err(FendError::ExpectedAString);
+    };
+    let weight = formula_weight(formula.as_ref(), int)?;
+    let unit = evaluate_to_value("g/mol", None, context, int)?.expect_num()?;
+    weight.mul(unit, int)
+}
@@ -86,6 +86,27 @@ pub(crate) enum FendError {
     RootsOfNegativeNumbers,
     NonIntegerNegRoots,
     CannotConvertValueTo(&'static str),
+    NoSolutionFound,
+    TooManySolutions,
+    FunctionMustReturnANumber(&'static str),
+    DoesNotConverge(&'static str),
+    NotDifferentiable,
+    ExpectedPositiveValue(&'static str),
+    DifferentModuli,
+    NoModularInverse,
+    UnsupportedModularOperation,
+    ExpectedAnAngle,
+    ExpectedAList,
+    UnitAlreadyDefined(String),
+    UnitDefinitionError(usize, Box<Self>),
+    UnsupportedUnitDefinition(String),
+    AbsoluteTemperatureAddition,
+    AbsoluteTemperatureSubtraction,
+    ExpectedAMassOrVolume,
+    ExpectedAnAmountOrMass,
+    InvalidChemicalFormula(String),
+    UnknownElement(String),
+    AmbiguousUnit(String, Vec<(String, String)>),
 }
 
 impl fmt::Display for FendError {
@@ -128,6 +149,60 @@ impl fmt::Display for FendError {
                 write!(f, "modulo is only supported for positive integers")
             }
             Self::CannotConvertValueTo(ty) => write!(f, "cannot convert value to {ty}"),
+            Self::NoSolutionFound => write!(f, "unable to find a real solution"),
+            Self::TooManySolutions => write!(f, "the equation has too many solutions"),
+            Self::FunctionMustReturnANumber(t) => {
+                write!(f, "function must return a number (found {t})")
+            }
+            Self::DoesNotConverge(name) => write!(f, "{name} does not converge"),
+            Self::NotDifferentiable => write!(f, "the function is not differentiable here"),
+            Self::ExpectedPositiveValue(name) => write!(f, "{name} must be positive"),
+            Self::DifferentModuli => write!(f, "cannot combine integers with different moduli"),
+            Self::NoModularInverse => write!(f, "modular inverse does not exist"),
+            Self::ExpectedAnAngle => write!(f, "expected an angle, e.g. 30\u{b0} or 0.5 rad"),
+            Self::ExpectedAList => write!(f, "expected a non-empty list, e.g. [-100, 60, 60]"),
+            Self::UnitAlreadyDefined(name) => {
+                write!(f, "cannot redefine the built-in unit '{name}'")
+            }
+            Self::UnitDefinitionError(line, e) => {
+                write!(f, "invalid unit definition on line {line}: {e}")
+            }
+            Self::UnsupportedUnitDefinition(name) => {
+                write!(f, "unsupported unit definition '{name}'")
+            }
+            Self::AbsoluteTemperatureAddition => write!(
+                f,
+                "cannot add two absolute temperatures, use a temperature difference like 5 \u{394}\u{b0}C instead"
+            ),
+            Self::AbsoluteTemperatureSubtraction => write!(
+                f,
+                "cannot subtract an absolute temperature from a temperature difference"
+            ),
+            Self::ExpectedAMassOrVolume => write!(
+                f,
+                "a substance can only be combined with a mass or a volume, e.g. `1 cup flour`"
+            ),
+            Self::ExpectedAnAmountOrMass => write!(
+                f,
+                "a chemical formula can only be combined with an amount of substance or a mass, e.g. `2 mol of \"NaCl\"`"
+            ),
+            Self::InvalidChemicalFormula(s) => write!(f, "invalid chemical formula '{s}'"),
+            Self::UnknownElement(s) => write!(f, "unknown element '{s}'"),
+            Self::AmbiguousUnit(ident, candidates) => {
+                write!(f, "ambiguous unit '{ident}', which could refer to ")?;
+                for (i, (name, description)) in candidates.iter().enumerate() {
+                    if i + 1 == candidates.len() {
+                        write!(f, " or ")?;
+                    } else if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "'{name}' ({description})")?;
+                }
+                Ok(())
+            }
+            Self::UnsupportedModularOperation => {
+                write!(f, "operation is not supported for modular integers")
+            }
             Self::BaseTooSmall => write!(f, "base must be at least 2"),
             Self::ConversionRhsNumerical => write!(
                 f,
@@ -5,6 +5,7 @@ use crate::{
     error::{FendError, Interrupt},
     lexer, parser,
     scope::Scope,
+    units,
     value::Value,
     Span,
 };
@@ -15,6 +16,12 @@ pub(crate) fn evaluate_to_value<'a, I: Interrupt>(
     context: &mut crate::Context,
     int: &I,
 ) -> Result<Value, FendError> {
+    let parsed = parse(input, int)?;
+    let result = ast::evaluate(parsed, scope, context, int)?;
+    Ok(result)
+}
+
+fn parse<I: Interrupt>(input: &str, int: &I) -> Result<ast::Expr, FendError> {
     let lex = lexer::lex(input, int);
     let mut tokens = vec![];
     let mut missing_open_parens: i32 = 0;
@@ -28,9 +35,7 @@ pub(crate) fn evaluate_to_value<'a, I: Interrupt>(
     for _ in 0..missing_open_parens {
         tokens.insert(0, lexer::Token::Symbol(lexer::Symbol::OpenParens));
     }
-    let parsed = parser::parse_tokens(&tokens)?;
-    let result = ast::evaluate(parsed, scope, context, int)?;
-    Ok(result)
+    Ok(parser::parse_tokens(&tokens)?)
 }
 
 /// This also saves the calculation result in a variable `_` and `ans`
@@ -44,7 +49,20 @@ pub(crate) fn evaluate_to_spans<'a, I: Interrupt>(
         input = remaining;
         true
     });
-    let value = evaluate_to_value(input, scope, context, int)?;
+    let parsed = parse(input, int)?;
+    // explicit conversions like `to mF` take precedence over the preferred
+    // unit system and automatic prefixes
+    let is_conversion = parsed.is_conversion();
+    let mut value = ast::evaluate(parsed, scope, context, int)?;
+    if !is_conversion {
+        if let Value::Num(num) = value {
+            let mut num = units::convert_to_unit_system(*num, context, int)?;
+            if context.auto_prefix {
+                num = units::auto_prefix(num, context, int)?;
+            }
+            value = Value::Num(Box::new(num));
+        }
+    }
     context.variables.insert("_".to_string(), value.clone());
     context.variables.insert("ans".to_string(), value.clone());
     Ok((
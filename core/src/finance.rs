@@ -0,0 +1,209 @@
+use crate::error::{FendError, Interrupt};
+use crate::eval::evaluate_to_value;
+use crate::interrupt::test_int;
+use crate::num::Number;
+use crate::value::Value;
+
+const MAX_ITERATIONS: usize = 200;
+
+// interest rates (per period) at which `irr` looks for a sign change of the NPV
+const IRR_RATES: [f64; 17] = [
+    -0.99, -0.9, -0.75, -0.5, -0.25, -0.1, -0.05, 0.0, 0.05, 0.1, 0.2, 0.35, 0.5, 1.0, 2.0, 5.0,
+    10.0,
+];
+
+fn unitless<I: Interrupt>(n: Number, int: &I) -> Result<Number, FendError> {
+    n.convert_to(1.into(), int)
+}
+
+/// Returns the interest rate per period and the number of periods. If
+/// `periods` has a unit (e.g. `360 months`), each period is one of that unit,
+/// so an annual rate like `6%/year` is converted into a monthly rate.
+fn rate_per_period<I: Interrupt>(
+    rate: Number,
+    periods: Number,
+    int: &I,
+) -> Result<(Number, Number), FendError> {
+    let period = periods.clone().unit_one();
+    let rate = if rate.is_zero() {
+        Number::from(0)
+    } else {
+        unitless(rate.mul(period.clone(), int)?, int)?
+    };
+    let periods = unitless(periods.div(period, int)?, int)?;
+    Ok((rate, periods))
+}
+
+/// Returns `(1 + rate)^periods`
+fn growth<I: Interrupt>(rate: &Number, periods: Number, int: &I) -> Result<Number, FendError> {
+    Number::from(1).add(rate.clone(), int)?.pow(periods, int)
+}
+
+/// Computes the payment per period needed to pay off `principal` (plus
+/// interest) over the given number of periods
+pub(crate) fn pmt<I: Interrupt>(
+    rate: Number,
+    periods: Number,
+    principal: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let (rate, periods) = rate_per_period(rate, periods, int)?;
+    if rate.is_zero() {
+        return principal.div(periods, int);
+    }
+    let growth = growth(&rate, periods, int)?;
+    principal
+        .mul(rate, int)?
+        .mul(growth.clone(), int)?
+        .div(growth.sub(1.into(), int)?, int)
+}
+
+/// Computes the present value of a series of equal payments, i.e. the
+/// principal that these payments would pay off
+pub(crate) fn pv<I: Interrupt>(
+    rate: Number,
+    periods: Number,
+    payment: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let (rate, periods) = rate_per_period(rate, periods, int)?;
+    if rate.is_zero() {
+        return payment.mul(periods, int);
+    }
+    let growth = growth(&rate, periods, int)?;
+    payment
+        .mul(growth.clone().sub(1.into(), int)?, int)?
+        .div(rate.mul(growth, int)?, int)
+}
+
+/// Computes the future value of a series of equal payments made at the
+/// end of each period
+pub(crate) fn fv<I: Interrupt>(
+    rate: Number,
+    periods: Number,
+    payment: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let (rate, periods) = rate_per_period(rate, periods, int)?;
+    if rate.is_zero() {
+        return payment.mul(periods, int);
+    }
+    payment
+        .mul(growth(&rate, periods, int)?.sub(1.into(), int)?, int)?
+        .div(rate, int)
+}
+
+/// Computes `principal * (1 + rate / frequency)^(frequency * time)`, e.g.
+/// `compound (1000 USD) (5%/year) (12/year) (10 years)` for monthly compounding
+pub(crate) fn compound<I: Interrupt>(
+    principal: Number,
+    rate: Number,
+    frequency: Number,
+    time: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let periods = unitless(frequency.clone().mul(time, int)?, int)?;
+    let rate = unitless(rate.div(frequency, int)?, int)?;
+    principal.mul(growth(&rate, periods, int)?, int)
+}
+
+fn expect_cash_flows(cash_flows: Value) -> Result<Vec<Number>, FendError> {
+    let Value::List(items) = cash_flows else {
+        return Err(FendError::ExpectedAList);
+    };
+    if items.is_empty() {
+        return Err(FendError::ExpectedAList);
+    }
+    items.into_iter().map(Value::expect_num).collect()
+}
+
+/// Computes the net present value of a list of cash flows, where the first
+/// cash flow occurs immediately and each following one a period later
+pub(crate) fn npv<I: Interrupt>(
+    rate: Number,
+    cash_flows: Value,
+    int: &I,
+) -> Result<Number, FendError> {
+    // a rate like `10%/year` means that the cash flows are a year apart
+    let rate = if rate.is_unitless(int)? {
+        rate
+    } else {
+        let period = Number::from(1).div(rate.clone().simplify(int)?.unit_one(), int)?;
+        rate_per_period(rate, period, int)?.0
+    };
+    let discount = Number::from(1).add(rate, int)?;
+    let mut factor = Number::from(1);
+    let mut result: Option<Number> = None;
+    for cash_flow in expect_cash_flows(cash_flows)? {
+        test_int(int)?;
+        let value = cash_flow.div(factor.clone(), int)?;
+        result = Some(match result {
+            Some(result) => result.add(value, int)?,
+            None => value,
+        });
+        factor = factor.mul(discount.clone(), int)?;
+    }
+    result.ok_or(FendError::ExpectedAList)
+}
+
+/// Evaluates the net present value at `rate` using floating-point numbers
+fn npv_f64(rate: f64, cash_flows: &[f64]) -> f64 {
+    let mut factor = 1.0;
+    let mut result = 0.0;
+    for cash_flow in cash_flows {
+        result += cash_flow / factor;
+        factor *= 1.0 + rate;
+    }
+    result
+}
+
+/// Finds the internal rate of return of a list of cash flows, i.e. the
+/// interest rate at which their net present value is zero
+pub(crate) fn irr<I: Interrupt>(
+    cash_flows: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let cash_flows = expect_cash_flows(cash_flows)?;
+    let unit = cash_flows[0].clone().unit_one();
+    let mut values = Vec::with_capacity(cash_flows.len());
+    for cash_flow in cash_flows {
+        values.push(cash_flow.convert_to(unit.clone(), int)?.try_as_f64(int)?);
+    }
+    for bracket in IRR_RATES.windows(2) {
+        let (mut a, mut b) = (bracket[0], bracket[1]);
+        let mut fa = npv_f64(a, &values);
+        if fa == 0.0 {
+            return rate_as_percentage(a, context, int);
+        }
+        if fa.signum() == npv_f64(b, &values).signum() {
+            continue;
+        }
+        for _ in 0..MAX_ITERATIONS {
+            test_int(int)?;
+            let mid = a + (b - a) / 2.0;
+            let f_mid = npv_f64(mid, &values);
+            if f_mid.signum() == fa.signum() {
+                a = mid;
+                fa = f_mid;
+            } else {
+                b = mid;
+            }
+            if b - a <= 1e-15 * a.abs().max(1.0) {
+                break;
+            }
+        }
+        return rate_as_percentage(a + (b - a) / 2.0, context, int);
+    }
+    Err(FendError::NoSolutionFound)
+}
+
+fn rate_as_percentage<I: Interrupt>(
+    rate: f64,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let percent = evaluate_to_value("%", None, context, int)?.expect_num()?;
+    let rate = evaluate_to_value(&format!("{rate:.9e}"), None, context, int)?.expect_num()?;
+    Ok(rate.convert_to(percent, int)?.make_approximate())
+}
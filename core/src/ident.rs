@@ -22,9 +22,9 @@ impl Ident {
     }
 
     pub(crate) fn is_prefix_unit(&self) -> bool {
-        // when changing this also make sure to change number output formatting
-        // lexer identifier splitting
-        self.0 == "$" || self.0 == "\u{a3}"
+        // when changing this also make sure to change lexer identifier splitting
+        // (output formatting uses the currency metadata in `units::builtin`)
+        self.0 == "$" || self.0 == "\u{a3}" || self.0 == "\u{a5}" || self.0 == "\u{20ac}"
     }
 
     pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
@@ -34,6 +34,11 @@ pub(crate) enum Symbol {
     ShiftRight,
     Semicolon,
     Equals, // used for assignment
+    Angle,  // polar form of complex numbers, e.g. `5 ∠ 30°`
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    At, // prefix rule of a unit definition, e.g. `unit l@smoot = 67 in`
 }
 
 impl fmt::Display for Symbol {
@@ -60,6 +65,11 @@ impl fmt::Display for Symbol {
             Self::ShiftRight => ">>",
             Self::Semicolon => ";",
             Self::Equals => "=",
+            Self::Angle => "\u{2220}",
+            Self::OpenBracket => "[",
+            Self::CloseBracket => "]",
+            Self::Comma => ",",
+            Self::At => "@",
         };
         write!(f, "{s}")?;
         Ok(())
@@ -371,7 +381,10 @@ fn parse_basic_number<'a, I: Interrupt>(
     Ok((res, input))
 }
 
-fn parse_number<'a, I: Interrupt>(input: &'a str, int: &I) -> Result<(Number, &'a str), FendError> {
+pub(crate) fn parse_number<'a, I: Interrupt>(
+    input: &'a str,
+    int: &I,
+) -> Result<(Number, &'a str), FendError> {
     let (base, input) = parse_base_prefix(input).unwrap_or((Base::default(), input));
     let (res, input) = parse_basic_number(input, base, int)?;
     Ok((res, input))
@@ -379,18 +392,18 @@ fn parse_number<'a, I: Interrupt>(input: &'a str, int: &I) -> Result<(Number, &'
 
 fn is_valid_in_ident(ch: char, prev: Option<char>) -> bool {
     let allowed_chars = [
-        ',', '_', '⅛', '¼', '⅜', '½', '⅝', '¾', '⅞', '⅙', '⅓', '⅔', '⅚', '⅕', '⅖', '⅗', '⅘', '°',
-        '$', '℃', '℉', '℧', '℈', '℥', '℔', '¢', '£', '¥', '€', '₩', '₪', '₤', '₨', '฿', '₡', '₣',
-        '₦', '₧', '₫', '₭', '₮', '₯', '₱', '﷼', '﹩', '￠', '￡', '￥', '￦', '㍱', '㍲', '㍳',
-        '㍴', '㍶', '㎀', '㎁', '㎂', '㎃', '㎄', '㎅', '㎆', '㎇', '㎈', '㎉', '㎊', '㎋', '㎌',
-        '㎍', '㎎', '㎏', '㎐', '㎑', '㎒', '㎓', '㎔', '㎕', '㎖', '㎗', '㎘', '㎙', '㎚', '㎛',
-        '㎜', '㎝', '㎞', '㎟', '㎠', '㎡', '㎢', '㎣', '㎤', '㎥', '㎦', '㎧', '㎨', '㎩', '㎪',
-        '㎫', '㎬', '㎭', '㎮', '㎯', '㎰', '㎱', '㎲', '㎳', '㎴', '㎵', '㎶', '㎷', '㎸', '㎹',
-        '㎺', '㎻', '㎼', '㎽', '㎾', '㎿', '㏀', '㏁', '㏃', '㏄', '㏅', '㏆', '㏈', '㏉', '㏊',
-        '㏌', '㏏', '㏐', '㏓', '㏔', '㏕', '㏖', '㏗', '㏙', '㏛', '㏜', '㏝',
+        '_', '⅛', '¼', '⅜', '½', '⅝', '¾', '⅞', '⅙', '⅓', '⅔', '⅚', '⅕', '⅖', '⅗', '⅘', '°', '$',
+        '℃', '℉', '℧', '℈', '℥', '℔', '¢', '£', '¥', '€', '₩', '₪', '₤', '₨', '฿', '₡', '₣', '₦',
+        '₧', '₫', '₭', '₮', '₯', '₱', '﷼', '﹩', '￠', '￡', '￥', '￦', '㍱', '㍲', '㍳', '㍴',
+        '㍶', '㎀', '㎁', '㎂', '㎃', '㎄', '㎅', '㎆', '㎇', '㎈', '㎉', '㎊', '㎋', '㎌', '㎍',
+        '㎎', '㎏', '㎐', '㎑', '㎒', '㎓', '㎔', '㎕', '㎖', '㎗', '㎘', '㎙', '㎚', '㎛', '㎜',
+        '㎝', '㎞', '㎟', '㎠', '㎡', '㎢', '㎣', '㎤', '㎥', '㎦', '㎧', '㎨', '㎩', '㎪', '㎫',
+        '㎬', '㎭', '㎮', '㎯', '㎰', '㎱', '㎲', '㎳', '㎴', '㎵', '㎶', '㎷', '㎸', '㎹', '㎺',
+        '㎻', '㎼', '㎽', '㎾', '㎿', '㏀', '㏁', '㏃', '㏄', '㏅', '㏆', '㏈', '㏉', '㏊', '㏌',
+        '㏏', '㏐', '㏓', '㏔', '㏕', '㏖', '㏗', '㏙', '㏛', '㏜', '㏝',
     ];
     let only_valid_by_themselves = ['%', '‰', '‱', '′', '″', '’', '”', 'π'];
-    let split_on_subsequent_digit = ['$', '£'];
+    let split_on_subsequent_digit = ['$', '£', '¥', '€'];
     let always_invalid = ['λ'];
     if always_invalid.contains(&ch) {
         false
@@ -496,6 +509,11 @@ fn parse_symbol(ch: char, input: &mut &str) -> Result<Token, FendError> {
             }
         }
         ';' => Symbol::Semicolon,
+        '[' => Symbol::OpenBracket,
+        ']' => Symbol::CloseBracket,
+        ',' => Symbol::Comma,
+        '@' => Symbol::At,
+        '\u{2220}' => Symbol::Angle,
         _ => return Err(FendError::UnexpectedChar(ch)),
     }))
 }
@@ -653,6 +671,10 @@ pub(crate) struct Lexer<'a, 'b, I: Interrupt> {
     // normally 0; 1 after backslash; 2 after ident after backslash
     after_backslash_state: u8,
     after_number_or_to: bool,
+    after_ident: bool,
+    // for each open bracket or parenthesis, whether commas inside it
+    // separate list items or function arguments rather than digits
+    comma_separators: Vec<bool>,
     int: &'b I,
 }
 
@@ -692,8 +714,15 @@ impl<'a, 'b, I: Interrupt> Lexer<'a, 'b, I> {
                     || (ch == '.' && self.after_backslash_state == 0)
                     || (ch == 'd' && following.is_some() && following.unwrap().is_ascii_digit())
                 {
-                    let (num, remaining) = parse_number(self.input, self.int)?;
-                    self.input = remaining;
+                    // commas separate list items and arguments, e.g. in
+                    // `[1,2]` or `f(1,2)`, but are digit separators elsewhere
+                    let end = if self.comma_separators.last() == Some(&true) {
+                        self.input.find(',').unwrap_or(self.input.len())
+                    } else {
+                        self.input.len()
+                    };
+                    let (num, remaining) = parse_number(&self.input[..end], self.int)?;
+                    self.input = &self.input[end - remaining.len()..];
                     Token::Num(num)
                 } else if ch == '\'' || ch == '"' {
                     if self.after_number_or_to {
@@ -752,6 +781,18 @@ impl<'a, I: Interrupt> Iterator for Lexer<'a, '_, I> {
         } else {
             self.after_number_or_to = false;
         }
+        match res {
+            Some(Ok(Token::Symbol(Symbol::OpenBracket))) => self.comma_separators.push(true),
+            Some(Ok(Token::Symbol(Symbol::OpenParens))) => {
+                // parentheses directly after an identifier are a function call
+                self.comma_separators.push(self.after_ident);
+            }
+            Some(Ok(Token::Symbol(Symbol::CloseBracket | Symbol::CloseParens))) => {
+                self.comma_separators.pop();
+            }
+            _ => (),
+        }
+        self.after_ident = matches!(res, Some(Ok(Token::Ident(_))));
         if let Some(Ok(Token::Symbol(Symbol::Backslash))) = res {
             self.after_backslash_state = 1;
         } else if self.after_backslash_state == 1 {
@@ -772,6 +813,8 @@ pub(crate) fn lex<'a, 'b, I: Interrupt>(input: &'a str, int: &'b I) -> Lexer<'a,
         input,
         after_backslash_state: 0,
         after_number_or_to: false,
+        after_ident: false,
+        comma_separators: vec![],
         int,
     }
 }
@@ -21,9 +21,12 @@
 //! ```
 
 mod ast;
+mod astronomy;
+mod chemistry;
 mod date;
 mod error;
 mod eval;
+mod finance;
 mod format;
 mod ident;
 mod inline_substitutions;
@@ -31,6 +34,7 @@ mod interrupt;
 mod json;
 mod lexer;
 mod num;
+mod numerical;
 mod parser;
 mod scope;
 mod serialize;
@@ -49,6 +53,7 @@ pub struct FendResult {
     plain_result: String,
     span_result: Vec<Span>,
     is_unit: bool, // is this the () type
+    warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -121,11 +126,18 @@ impl FendResult {
         self.is_unit
     }
 
+    /// Returns warnings about the computation, e.g. when a unit was found
+    /// by ignoring its capitalization. Most calculations have no warnings.
+    pub fn get_warnings(&self) -> impl Iterator<Item = &str> {
+        self.warnings.iter().map(String::as_str)
+    }
+
     fn empty() -> Self {
         Self {
             plain_result: String::new(),
             span_result: vec![],
             is_unit: true,
+            warnings: vec![],
         }
     }
 }
@@ -148,6 +160,14 @@ enum OutputMode {
     TerminalFixedWidth,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UnitSystem {
+    Default,
+    Metric,
+    Imperial,
+    SiBase,
+}
+
 type ExchangeRateFn = fn(&str) -> Result<f64, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
 /// This struct contains context used for `fend`. It should only be created once
@@ -160,6 +180,13 @@ pub struct Context {
     random_u32: Option<fn() -> u32>,
     output_mode: OutputMode,
     get_exchange_rate: Option<ExchangeRateFn>,
+    bankers_rounding: bool,
+    custom_units: Vec<units::CustomUnit>,
+    pixels_per_inch: num::Number,
+    base_font_size: num::Number,
+    auto_prefix: bool,
+    unit_system: UnitSystem,
+    warnings: Vec<String>,
 }
 
 impl fmt::Debug for Context {
@@ -170,6 +197,13 @@ impl fmt::Debug for Context {
             .field("fc_mode", &self.fc_mode)
             .field("random_u32", &self.random_u32)
             .field("output_mode", &self.output_mode)
+            .field("bankers_rounding", &self.bankers_rounding)
+            .field("custom_units", &self.custom_units)
+            .field("pixels_per_inch", &self.pixels_per_inch)
+            .field("base_font_size", &self.base_font_size)
+            .field("auto_prefix", &self.auto_prefix)
+            .field("unit_system", &self.unit_system)
+            .field("warnings", &self.warnings)
             .finish_non_exhaustive()
     }
 }
@@ -192,6 +226,13 @@ impl Context {
             random_u32: None,
             output_mode: OutputMode::SimpleText,
             get_exchange_rate: None,
+            bankers_rounding: false,
+            custom_units: vec![],
+            pixels_per_inch: 96.into(),
+            base_font_size: 16.into(),
+            auto_prefix: false,
+            unit_system: UnitSystem::Default,
+            warnings: vec![],
         }
     }
 
@@ -235,16 +276,107 @@ impl Context {
         self.output_mode = OutputMode::TerminalFixedWidth;
     }
 
+    /// Round currency amounts to the nearest even minor unit when they are
+    /// exactly halfway between two values (banker's rounding), instead of
+    /// rounding away from zero.
+    pub fn use_bankers_rounding(&mut self) {
+        self.bankers_rounding = true;
+    }
+
+    /// Set the pixel density used for the `px` unit. The default is 96
+    /// pixels per inch, as used by CSS.
+    ///
+    /// # Errors
+    /// Returns an error if the pixel density is zero.
+    pub fn set_pixels_per_inch(&mut self, pixels_per_inch: u64) -> Result<(), String> {
+        if pixels_per_inch == 0 {
+            return Err(FendError::ExpectedPositiveValue("pixel density").to_string());
+        }
+        self.pixels_per_inch = pixels_per_inch.into();
+        Ok(())
+    }
+
+    /// Set the font size used for the `em` and `rem` units, in pixels.
+    /// The default is 16 pixels.
+    ///
+    /// # Errors
+    /// Returns an error if the font size is zero.
+    pub fn set_base_font_size(&mut self, pixels: u64) -> Result<(), String> {
+        if pixels == 0 {
+            return Err(FendError::ExpectedPositiveValue("base font size").to_string());
+        }
+        self.base_font_size = pixels.into();
+        Ok(())
+    }
+
+    /// Automatically choose the SI or binary prefix of the first unit in
+    /// each result, e.g. showing `0.000000047 F` as `47 nF`, unless the
+    /// result is explicitly converted to another unit. This is the same
+    /// as adding `to auto_prefix` to every calculation.
+    pub fn use_auto_prefix(&mut self) {
+        self.auto_prefix = true;
+    }
+
+    /// Convert results with compound units like `mph h` into metric units,
+    /// e.g. showing `60 mph * 2 h` as `193.12128 km`, unless the result is
+    /// explicitly converted to another unit.
+    pub fn use_metric_units(&mut self) {
+        self.unit_system = UnitSystem::Metric;
+    }
+
+    /// Convert results with compound units like `lb ft / s^2` into imperial
+    /// (or US customary) units, e.g. `lbf`, unless the result is explicitly
+    /// converted to another unit.
+    pub fn use_imperial_units(&mut self) {
+        self.unit_system = UnitSystem::Imperial;
+    }
+
+    /// Convert results with compound units into SI base units, e.g. showing
+    /// `60 mph * 2 h` in meters, unless the result is explicitly converted
+    /// to another unit.
+    pub fn use_si_base_units(&mut self) {
+        self.unit_system = UnitSystem::SiBase;
+    }
+
+    /// Adds a warning to the result of the current calculation
+    pub(crate) fn add_warning(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Loads additional unit definitions from a string in the format used
+    /// by GNU units, e.g. `oilbarrel 42 gallons`. Each line defines a unit
+    /// by its name followed by its definition, names ending in `-` define
+    /// prefixes, `!` defines a new base unit, and `#` starts a comment.
+    /// Function and table definitions, which aren't supported, and units
+    /// that are already built in are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if a definition is invalid. Any units defined on
+    /// earlier lines are kept.
+    pub fn load_unit_definitions(&mut self, definitions: &str) -> Result<(), String> {
+        match units::load_definitions(definitions, self, &interrupt::Never::default()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
     fn serialize_variables_internal(&self, write: &mut impl io::Write) -> Result<(), FendError> {
         serialize_usize(self.variables.len(), write)?;
         for (k, v) in &self.variables {
             serialize_string(k.as_str(), write)?;
             v.serialize(write)?;
         }
+        serialize_usize(self.custom_units.len(), write)?;
+        for unit in &self.custom_units {
+            unit.serialize(write)?;
+        }
         Ok(())
     }
 
-    /// Serializes all variables defined in this context to a stream of bytes.
+    /// Serializes all variables and units defined in this context to a stream
+    /// of bytes.
     /// Note that the specific format is NOT stable, and can change with any
     /// minor update. It is also not cross-platform compatible.
     ///
@@ -268,11 +400,18 @@ impl Context {
             self.variables
                 .insert(deserialize_string(read)?, value::Value::deserialize(read)?);
         }
+        let len = deserialize_usize(read)?;
+        self.custom_units.clear();
+        self.custom_units.reserve(len);
+        for _ in 0..len {
+            self.custom_units
+                .push(units::CustomUnit::deserialize(read)?);
+        }
         Ok(())
     }
 
-    /// Deserializes the given variables, replacing all prior variables in
-    /// the given context.
+    /// Deserializes the given variables and units, replacing all prior
+    /// variables and units in the given context.
     ///
     /// # Errors
     /// Returns an error if the input byte stream is invalid and cannot be
@@ -310,6 +449,7 @@ fn evaluate_with_interrupt_internal(
         // no or blank input: return no output
         return Ok(FendResult::empty());
     }
+    context.warnings.clear();
     let (result, is_unit) = match eval::evaluate_to_spans(input, None, context, int) {
         Ok(value) => value,
         Err(e) => return Err(e.to_string()),
@@ -322,6 +462,7 @@ fn evaluate_with_interrupt_internal(
         plain_result,
         span_result: result,
         is_unit,
+        warnings: std::mem::take(&mut context.warnings),
     })
 }
 
@@ -396,6 +537,93 @@ impl Completion {
     }
 }
 
+/// Documentation for a unit or constant, e.g. for showing tooltips. This is
+/// also what `describe kelvin` or `help kelvin` shows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitDescription {
+    name: String,
+    description: String,
+    definition: String,
+    base_units: String,
+    aliases: Vec<String>,
+    uncertainty: String,
+}
+
+impl UnitDescription {
+    /// The name of the unit, e.g. `newton`
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A short description, e.g. `force`. This may be empty.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// How the unit is defined, e.g. `kg m / s^2`
+    #[must_use]
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+
+    /// The dimension of the unit in terms of base units, e.g.
+    /// `kilogram meter second^-2`. This is empty if it can't be determined,
+    /// e.g. for currencies without exchange rates.
+    #[must_use]
+    pub fn base_units(&self) -> &str {
+        &self.base_units
+    }
+
+    /// Other names for this unit, e.g. `newtons` and `N`
+    #[must_use]
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// The standard uncertainty of a physical constant (e.g. `2.8e-40 kg`
+    /// for the electron mass), or `exact` for constants that are exact in
+    /// the SI. This is empty for other units.
+    #[must_use]
+    pub fn uncertainty(&self) -> &str {
+        &self.uncertainty
+    }
+}
+
+impl fmt::Display for UnitDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.description.is_empty() {
+            write!(f, ": {}", self.description)?;
+        }
+        if !self.definition.is_empty() {
+            write!(f, "\ndefinition: {}", self.definition)?;
+        }
+        if !self.base_units.is_empty() {
+            write!(f, "\nbase units: {}", self.base_units)?;
+        }
+        if !self.aliases.is_empty() {
+            write!(f, "\naliases: {}", self.aliases.join(", "))?;
+        }
+        if !self.uncertainty.is_empty() {
+            write!(f, "\nuncertainty: {}", self.uncertainty)?;
+        }
+        Ok(())
+    }
+}
+
+/// Looks up the documentation for a unit or constant, e.g. `kelvin` or `N`.
+///
+/// # Errors
+/// Returns an error if there is no unit with the given name.
+pub fn describe_unit(name: &str, context: &mut Context) -> Result<UnitDescription, String> {
+    match units::describe_unit(name, context, &interrupt::Never::default()) {
+        Ok(description) => Ok(description),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[must_use]
 pub fn get_completions_for_prefix(mut prefix: &str) -> (usize, Vec<Completion>) {
     let mut prepend = "";
@@ -445,6 +673,8 @@ pub mod test_utils {
             "EUR" => 1.0,
             "HKD" => 8.0,
             "AUD" => 1.3,
+            "JPY" => 150.0,
+            "KWD" => 0.3,
             _ => panic!("unknown currency {currency}"),
         })
     }
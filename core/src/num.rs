@@ -7,10 +7,13 @@ mod complex;
 mod dist;
 mod exact;
 mod formatting_style;
+mod modular;
 mod real;
 mod unit;
 
+pub(crate) use complex::ComplexFormat;
 pub(crate) use formatting_style::FormattingStyle;
+pub(crate) use modular::Modular;
 
 use crate::error::FendError;
 
@@ -147,8 +147,38 @@ impl BigRat {
         self.num.try_as_usize(int)
     }
 
+    /// Returns whether this integer is negative, together with its absolute value
+    pub(crate) fn try_as_integer<I: Interrupt>(
+        mut self,
+        int: &I,
+    ) -> Result<(bool, BigUint), FendError> {
+        self = self.simplify(int)?;
+        if self.den != 1.into() {
+            return Err(FendError::FractionToInteger);
+        }
+        Ok((
+            self.sign == Sign::Negative && self.num != 0.into(),
+            self.num,
+        ))
+    }
+
+    /// Rounds towards zero, e.g. `-7/2` becomes `-3`
+    pub(crate) fn truncate<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            sign: self.sign,
+            num: self.num.divmod(&self.den, int)?.0,
+            den: 1.into(),
+        })
+    }
+
     pub(crate) fn into_f64<I: Interrupt>(mut self, int: &I) -> Result<f64, FendError> {
         self = self.simplify(int)?;
+        // a huge numerator and denominator (e.g. for `e^-20`) would both
+        // overflow to infinity, so they are scaled down first
+        while !self.num.as_f64().is_finite() || !self.den.as_f64().is_finite() {
+            self.num = self.num.rshift_n(&64.into(), int)?;
+            self.den = self.den.rshift_n(&64.into(), int)?;
+        }
         let positive_result = self.num.as_f64() / self.den.as_f64();
         if self.sign == Sign::Positive {
             Ok(positive_result)
@@ -182,6 +212,89 @@ impl BigRat {
         })
     }
 
+    /// Converts a finite float into the exact fraction it represents,
+    /// i.e. its mantissa multiplied by a power of two
+    pub(crate) fn from_f64_exact<I: Interrupt>(f: f64, int: &I) -> Result<Self, FendError> {
+        if !f.is_finite() {
+            return Self::from_f64(f, int);
+        }
+        let bits = f.to_bits();
+        let biased_exponent = i32::try_from((bits >> 52) & 0x7ff).unwrap_or_default();
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+        let power_of_two =
+            BigUint::pow(&2.into(), &u64::from(exponent.unsigned_abs()).into(), int)?;
+        let mut res = Self::from(mantissa);
+        if exponent >= 0 {
+            res.num = res.num.mul(&power_of_two, int)?;
+        } else {
+            res.den = power_of_two;
+        }
+        res = res.simplify(int)?;
+        Ok(if f < 0.0 { -res } else { res })
+    }
+
+    /// Converts an approximate value, rounding it to 12 significant figures
+    /// so that floating-point errors (e.g. 29.999999999999996) disappear
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub(crate) fn from_f64_rounded<I: Interrupt>(f: f64, int: &I) -> Result<Self, FendError> {
+        if !f.is_normal() {
+            return Self::from_f64(f, int);
+        }
+        let exponent = f.abs().log10().floor() as i32;
+        let digits = (f.abs() / 10_f64.powi(exponent) * 1e11).round() as u64;
+        let scale = 11 - exponent;
+        let power_of_ten = BigUint::pow(&10.into(), &u64::from(scale.unsigned_abs()).into(), int)?;
+        let mut res = Self::from(digits);
+        if scale >= 0 {
+            res.den = power_of_ten;
+        } else {
+            res.num = res.num.mul(&power_of_ten, int)?;
+        }
+        Ok(if f < 0.0 { -res } else { res })
+    }
+
+    /// Rounds to the given number of decimal places. Ties are rounded away
+    /// from zero, or to the nearest even digit if `half_even` is set.
+    /// The result is exact iff no rounding was necessary.
+    pub(crate) fn round_to_decimal_places<I: Interrupt>(
+        self,
+        decimal_places: usize,
+        half_even: bool,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        let power_of_ten = BigUint::pow(&10.into(), &BigUint::from(decimal_places as u64), int)?;
+        let scaled = self.num.mul(&power_of_ten, int)?;
+        let (mut quotient, remainder) = scaled.divmod(&self.den, int)?;
+        let exact = remainder == 0.into();
+        let twice_remainder = remainder.mul(&2.into(), int)?;
+        if twice_remainder > self.den
+            || (twice_remainder == self.den && !(half_even && quotient.is_even(int)?))
+        {
+            quotient = quotient.add(&1.into());
+        }
+        Ok(Exact::new(
+            Self {
+                sign: if quotient == 0.into() {
+                    Sign::Positive
+                } else {
+                    self.sign
+                },
+                num: quotient,
+                den: power_of_ten,
+            },
+            exact,
+        ))
+    }
+
     // sin works for all real numbers
     pub(crate) fn sin<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
         Ok(if self == 0.into() {
@@ -887,6 +1000,14 @@ impl BigRat {
                 inverse_res.exact,
             ));
         }
+        if rhs.den > 1000.into() {
+            // exact roots of a high degree (e.g. for `2^0.000003`) take
+            // very long to compute, so they are approximated instead
+            let result = self.clone().into_f64(int)?.powf(rhs.clone().into_f64(int)?);
+            if result.is_finite() {
+                return Ok(Exact::new(Self::from_f64_exact(result, int)?, false));
+            }
+        }
         let result_sign = if self.sign == Sign::Positive || rhs.num.is_even(int)? {
             Sign::Positive
         } else {
@@ -382,6 +382,54 @@ impl BigUint {
         Ok(self.divmod(other, int)?.1)
     }
 
+    /// Computes `self^exponent mod modulus` using square-and-multiply
+    pub(crate) fn mod_pow<I: Interrupt>(
+        &self,
+        exponent: &Self,
+        modulus: &Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut result = Self::from(1).rem(modulus, int)?;
+        let mut base = self.rem(modulus, int)?;
+        let mut exponent = exponent.clone();
+        while !exponent.is_zero() {
+            test_int(int)?;
+            let (quotient, remainder) = exponent.divmod(&Self::from(2), int)?;
+            if !remainder.is_zero() {
+                result = result.mul(&base, int)?.rem(modulus, int)?;
+            }
+            base = base.clone().mul(&base, int)?.rem(modulus, int)?;
+            exponent = quotient;
+        }
+        Ok(result)
+    }
+
+    /// Computes the inverse of `self` modulo `modulus` using the extended
+    /// Euclidean algorithm, or returns `None` if it doesn't exist
+    pub(crate) fn mod_inverse<I: Interrupt>(
+        &self,
+        modulus: &Self,
+        int: &I,
+    ) -> Result<Option<Self>, FendError> {
+        // invariant: r == t * self (mod modulus)
+        let (mut old_r, mut r) = (self.rem(modulus, int)?, modulus.clone());
+        let (mut old_t, mut t) = (Self::from(1), Self::from(0));
+        while !r.is_zero() {
+            test_int(int)?;
+            let (quotient, remainder) = old_r.divmod(&r, int)?;
+            old_r = r;
+            r = remainder;
+            let qt = quotient.mul(&t, int)?.rem(modulus, int)?;
+            let new_t = old_t.add(modulus).sub(&qt).rem(modulus, int)?;
+            old_t = t;
+            t = new_t;
+        }
+        if old_r != 1.into() {
+            return Ok(None);
+        }
+        Ok(Some(old_t.rem(modulus, int)?))
+    }
+
     pub(crate) fn is_even<I: Interrupt>(&self, int: &I) -> Result<bool, FendError> {
         Ok(self.divmod(&Self::from(2), int)?.1 == 0.into())
     }
@@ -944,4 +992,31 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_mod_pow() -> Res {
+        let int = crate::interrupt::Never::default();
+        let pow = |a: u64, b: u64, n: u64| {
+            BigUint::from(a).mod_pow(&BigUint::from(b), &BigUint::from(n), &int)
+        };
+        assert_eq!(pow(3, 100, 13)?, BigUint::from(3));
+        assert_eq!(pow(2, 0, 7)?, BigUint::from(1));
+        assert_eq!(pow(2, 10, 1)?, BigUint::from(0));
+        assert_eq!(
+            pow(12_345, 6_789, 1_000_000_007)?,
+            BigUint::from(130_015_824)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mod_inverse() -> Res {
+        let int = crate::interrupt::Never::default();
+        let inv = |a: u64, n: u64| BigUint::from(a).mod_inverse(&BigUint::from(n), &int);
+        assert_eq!(inv(3, 7)?, Some(BigUint::from(5)));
+        assert_eq!(inv(10, 17)?, Some(BigUint::from(12)));
+        assert_eq!(inv(4, 8)?, None);
+        assert_eq!(inv(0, 5)?, None);
+        Ok(())
+    }
 }
@@ -1,7 +1,9 @@
 use crate::error::{FendError, Interrupt};
+use crate::num::biguint::BigUint;
 use crate::num::real::{self, Real};
 use crate::num::Exact;
 use crate::num::{Base, FormattingStyle};
+use crate::serialize::{deserialize_u8, serialize_u8};
 use std::cmp::Ordering;
 use std::ops::Neg;
 use std::{fmt, io};
@@ -29,6 +31,35 @@ pub(crate) enum UseParentheses {
     IfComplexOrFraction,
 }
 
+/// How complex numbers are displayed
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub(crate) enum ComplexFormat {
+    /// e.g. `3 + 4i`
+    #[default]
+    Cartesian,
+    /// Magnitude and angle in degrees, e.g. `5 ∠ 30°`
+    Polar,
+}
+
+impl ComplexFormat {
+    pub(crate) fn serialize(self, write: &mut impl io::Write) -> Result<(), FendError> {
+        let n = match self {
+            Self::Cartesian => 0,
+            Self::Polar => 1,
+        };
+        serialize_u8(n, write)?;
+        Ok(())
+    }
+
+    pub(crate) fn deserialize(read: &mut impl io::Read) -> Result<Self, FendError> {
+        Ok(match deserialize_u8(read)? {
+            0 => Self::Cartesian,
+            1 => Self::Polar,
+            _ => return Err(FendError::DeserializationError),
+        })
+    }
+}
+
 impl Complex {
     pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
         self.real.serialize(write)?;
@@ -50,6 +81,36 @@ impl Complex {
         self.real.try_as_usize(int)
     }
 
+    pub(crate) fn try_as_integer<I: Interrupt>(
+        self,
+        int: &I,
+    ) -> Result<(bool, BigUint), FendError> {
+        if self.imag != 0.into() {
+            return Err(FendError::ComplexToInteger);
+        }
+        self.real.try_as_integer(int)
+    }
+
+    pub(crate) fn round_to_decimal_places<I: Interrupt>(
+        self,
+        decimal_places: usize,
+        half_even: bool,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        let rounded =
+            self.expect_real()?
+                .round_to_decimal_places(decimal_places, half_even, int)?;
+        Ok(Exact::new(Self::from(rounded.value), rounded.exact))
+    }
+
+    pub(crate) fn truncate<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.truncate(int)?))
+    }
+
+    pub(crate) fn try_as_f64<I: Interrupt>(self, int: &I) -> Result<f64, FendError> {
+        self.expect_real()?.into_f64(int)
+    }
+
     pub(crate) fn conjugate(self) -> Self {
         Self {
             real: self.real,
@@ -139,6 +200,52 @@ impl Complex {
         })
     }
 
+    /// Returns the angle of this number in radians, in the range (-π, π]
+    pub(crate) fn arg<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
+        // multiples of π/4 can be represented exactly
+        let fraction_of_pi =
+            |num: u64, den: u64, negative: bool| -> Result<Exact<Self>, FendError> {
+                let res = Exact::new(Real::pi(), true)
+                    .mul(Exact::new(&Real::from(num), true), int)?
+                    .div(&Exact::new(Real::from(den), true), int)?;
+                let res = if negative { -res } else { res };
+                Ok(res.apply(Self::from))
+            };
+        let zero = Real::from(0);
+        if self.imag.is_zero() {
+            return if self.real < zero {
+                fraction_of_pi(1, 1, false)
+            } else {
+                Ok(Exact::new(0.into(), true))
+            };
+        }
+        if self.real.is_zero() {
+            return fraction_of_pi(1, 2, self.imag < zero);
+        }
+        if self.real == self.imag {
+            return if self.real > zero {
+                fraction_of_pi(1, 4, false)
+            } else {
+                fraction_of_pi(3, 4, true)
+            };
+        }
+        if self.real == -self.imag.clone() {
+            return if self.real > zero {
+                fraction_of_pi(1, 4, true)
+            } else {
+                fraction_of_pi(3, 4, false)
+            };
+        }
+        let ratio =
+            Exact::new(self.imag.clone(), true).div(&Exact::new(self.real.clone(), true), int)?;
+        let atan = Exact::new(ratio.value.atan(int)?, false).apply(Self::from);
+        if self.real > zero {
+            return Ok(atan);
+        }
+        // atan only covers the right half-plane, so rotate by π
+        atan.add(fraction_of_pi(1, 1, self.imag < zero)?, int)
+    }
+
     pub(crate) fn format<I: Interrupt>(
         &self,
         exact: bool,
@@ -222,6 +329,10 @@ impl Complex {
         ))
     }
 
+    pub(crate) fn is_real(&self) -> bool {
+        self.imag.is_zero()
+    }
+
     fn expect_real(self) -> Result<Real, FendError> {
         if self.imag.is_zero() {
             Ok(self.real)
@@ -0,0 +1,132 @@
+use crate::error::{FendError, Interrupt};
+use crate::num::biguint::BigUint;
+use crate::num::Number;
+use std::io;
+
+/// An integer in the ring of integers modulo `modulus`, e.g. `5 (mod 13)`.
+/// All arithmetic stays reduced, and division uses modular inverses.
+#[derive(Clone, Debug)]
+pub(crate) struct Modular {
+    value: BigUint,
+    modulus: BigUint,
+}
+
+impl Modular {
+    pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
+        self.value.serialize(write)?;
+        self.modulus.serialize(write)?;
+        Ok(())
+    }
+
+    pub(crate) fn deserialize(read: &mut impl io::Read) -> Result<Self, FendError> {
+        Ok(Self {
+            value: BigUint::deserialize(read)?,
+            modulus: BigUint::deserialize(read)?,
+        })
+    }
+
+    pub(crate) fn new<I: Interrupt>(
+        value: Number,
+        modulus: Number,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let (negative, modulus) = modulus.try_as_integer(int)?;
+        if negative {
+            return Err(FendError::ModuloForPositiveInts);
+        }
+        if modulus == 0.into() {
+            return Err(FendError::ModuloByZero);
+        }
+        Self::reduce(value, modulus, int)
+    }
+
+    fn reduce<I: Interrupt>(value: Number, modulus: BigUint, int: &I) -> Result<Self, FendError> {
+        let (negative, value) = value.try_as_integer(int)?;
+        let mut value = value.divmod(&modulus, int)?.1;
+        if negative && value != 0.into() {
+            value = modulus.clone().sub(&value);
+        }
+        Ok(Self { value, modulus })
+    }
+
+    /// Converts an integer into an element of the same ring as `self`
+    pub(crate) fn with_value<I: Interrupt>(
+        &self,
+        value: Number,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Self::reduce(value, self.modulus.clone(), int)
+    }
+
+    pub(crate) fn has_same_modulus(&self, other: &Self) -> bool {
+        self.modulus == other.modulus
+    }
+
+    pub(crate) fn value(&self) -> Number {
+        self.value.clone().into()
+    }
+
+    pub(crate) fn modulus(&self) -> Number {
+        self.modulus.clone().into()
+    }
+
+    fn with_reduced<I: Interrupt>(&self, value: &BigUint, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            value: value.divmod(&self.modulus, int)?.1,
+            modulus: self.modulus.clone(),
+        })
+    }
+
+    fn expect_same_modulus(&self, other: &Self) -> Result<(), FendError> {
+        if self.has_same_modulus(other) {
+            Ok(())
+        } else {
+            Err(FendError::DifferentModuli)
+        }
+    }
+
+    pub(crate) fn add<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Self, FendError> {
+        self.expect_same_modulus(rhs)?;
+        self.with_reduced(&self.value.clone().add(&rhs.value), int)
+    }
+
+    pub(crate) fn neg<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        self.with_reduced(&self.modulus.clone().sub(&self.value), int)
+    }
+
+    pub(crate) fn sub<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Self, FendError> {
+        self.expect_same_modulus(rhs)?;
+        self.add(&rhs.clone().neg(int)?, int)
+    }
+
+    pub(crate) fn mul<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Self, FendError> {
+        self.expect_same_modulus(rhs)?;
+        self.with_reduced(&self.value.clone().mul(&rhs.value, int)?, int)
+    }
+
+    pub(crate) fn inverse<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        match self.value.mod_inverse(&self.modulus, int)? {
+            Some(value) => Ok(Self {
+                value,
+                modulus: self.modulus,
+            }),
+            None => Err(FendError::NoModularInverse),
+        }
+    }
+
+    pub(crate) fn div<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Self, FendError> {
+        self.expect_same_modulus(rhs)?;
+        self.mul(&rhs.clone().inverse(int)?, int)
+    }
+
+    /// Computes `self^exponent` using modular exponentiation. Negative
+    /// exponents use the modular inverse.
+    pub(crate) fn pow<I: Interrupt>(self, exponent: Number, int: &I) -> Result<Self, FendError> {
+        let (negative, exponent) = exponent.try_as_integer(int)?;
+        let base = if negative { self.inverse(int)? } else { self };
+        Ok(Self {
+            value: base.value.mod_pow(&exponent, &base.modulus, int)?,
+            modulus: base.modulus,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use crate::error::{FendError, Interrupt};
 use crate::format::Format;
 use crate::num::bigrat::{BigRat, FormattedBigRat};
+use crate::num::biguint::BigUint;
 use crate::num::Exact;
 use crate::num::{Base, FormattingStyle};
 use crate::serialize::{deserialize_u8, serialize_u8};
@@ -111,6 +112,42 @@ impl Real {
         }
     }
 
+    pub(crate) fn try_as_integer<I: Interrupt>(
+        self,
+        int: &I,
+    ) -> Result<(bool, BigUint), FendError> {
+        match self.pattern {
+            Pattern::Simple(s) => s.try_as_integer(int),
+            Pattern::Pi(n) => {
+                if n == 0.into() {
+                    Ok((false, 0.into()))
+                } else {
+                    Err(FendError::CannotConvertToInteger)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn round_to_decimal_places<I: Interrupt>(
+        self,
+        decimal_places: usize,
+        half_even: bool,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        let rounded =
+            self.approximate(int)?
+                .round_to_decimal_places(decimal_places, half_even, int)?;
+        Ok(Exact::new(Self::from(rounded.value), rounded.exact))
+    }
+
+    pub(crate) fn truncate<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.approximate(int)?.truncate(int)?))
+    }
+
+    pub(crate) fn into_f64<I: Interrupt>(self, int: &I) -> Result<f64, FendError> {
+        self.approximate(int)?.into_f64(int)
+    }
+
     pub(crate) fn try_as_usize<I: Interrupt>(self, int: &I) -> Result<usize, FendError> {
         match self.pattern {
             Pattern::Simple(s) => s.try_as_usize(int),
@@ -1,14 +1,19 @@
 use crate::ast::{BitwiseBop, Bop};
 use crate::error::{FendError, Interrupt};
+use crate::num::bigrat::BigRat;
+use crate::num::biguint::BigUint;
 use crate::num::complex::{Complex, UseParentheses};
 use crate::num::dist::Dist;
-use crate::num::{Base, FormattingStyle};
+use crate::num::real::Real;
+use crate::num::{Base, ComplexFormat, FormattingStyle};
 use crate::scope::Scope;
 use crate::serialize::{deserialize_bool, deserialize_usize, serialize_bool, serialize_usize};
+use crate::units::{query_currency_format, CurrencyFormat};
 use crate::{ast, ident::Ident};
 use crate::{Span, SpanKind};
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::ops::Neg;
 use std::sync::Arc;
 use std::{fmt, io};
@@ -30,6 +35,7 @@ pub(crate) struct Value {
     exact: bool,
     base: Base,
     format: FormattingStyle,
+    complex_format: ComplexFormat,
     simplifiable: bool,
 }
 
@@ -40,6 +46,7 @@ impl Value {
         serialize_bool(self.exact, write)?;
         self.base.serialize(write)?;
         self.format.serialize(write)?;
+        self.complex_format.serialize(write)?;
         serialize_bool(self.simplifiable, write)?;
         Ok(())
     }
@@ -51,6 +58,7 @@ impl Value {
             exact: deserialize_bool(read)?,
             base: Base::deserialize(read)?,
             format: FormattingStyle::deserialize(read)?,
+            complex_format: ComplexFormat::deserialize(read)?,
             simplifiable: deserialize_bool(read)?,
         })
     }
@@ -69,6 +77,74 @@ impl Value {
         self.value.one_point()?.try_as_usize(int)
     }
 
+    /// Returns whether this integer is negative, together with its absolute value
+    pub(crate) fn try_as_integer<I: Interrupt>(
+        self,
+        int: &I,
+    ) -> Result<(bool, BigUint), FendError> {
+        if !self.is_unitless(int)? {
+            return Err(FendError::NumberWithUnitToInt);
+        }
+        if !self.exact {
+            return Err(FendError::InexactNumberToInt);
+        }
+        self.value.one_point()?.try_as_integer(int)
+    }
+
+    /// Returns the numerical part of this value as an `f64`, ignoring its unit
+    pub(crate) fn try_as_f64<I: Interrupt>(self, int: &I) -> Result<f64, FendError> {
+        self.value.one_point()?.try_as_f64(int)
+    }
+
+    /// Returns the numerical part of this value without its unit
+    pub(crate) fn without_unit(self) -> Self {
+        Self {
+            unit: Unit::unitless(),
+            ..self
+        }
+    }
+
+    /// Converts a volume into a mass, or a mass into a volume, using the
+    /// density of a substance, e.g. for `1 cup flour`
+    pub(crate) fn apply_density<I: Interrupt>(
+        self,
+        density: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if self.unit.has_base_units(&[("meter", 3)], int)? {
+            self.mul(density, int)
+        } else if self.unit.has_base_units(&[("kilogram", 1)], int)? {
+            self.div(density, int)
+        } else {
+            Err(FendError::ExpectedAMassOrVolume)
+        }
+    }
+
+    /// Converts an amount of substance into a mass, or a mass into an
+    /// amount of substance, e.g. for `2 mol of "NaCl"`
+    pub(crate) fn apply_molar_mass<I: Interrupt>(
+        self,
+        molar_mass: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if self.unit.has_base_units(&[("mole", 1)], int)? {
+            self.mul(molar_mass, int)
+        } else if self.unit.has_base_units(&[("kilogram", 1)], int)? {
+            self.div(molar_mass, int)
+        } else {
+            Err(FendError::ExpectedAnAmountOrMass)
+        }
+    }
+
+    /// Returns an exact `1` with the same unit as this value
+    pub(crate) fn unit_one(self) -> Self {
+        Self {
+            value: Dist::from(1),
+            exact: true,
+            ..self
+        }
+    }
+
     pub(crate) fn create_unit_value_from_value<I: Interrupt>(
         value: &Self,
         prefix: Cow<'static, str>,
@@ -102,17 +178,35 @@ impl Value {
             unit: self.unit,
             exact: self.exact,
             base: self.base,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
             format,
         }
     }
 
+    pub(crate) fn with_complex_format(self, complex_format: ComplexFormat) -> Self {
+        Self {
+            value: self.value,
+            unit: self.unit,
+            exact: self.exact,
+            base: self.base,
+            format: self.format,
+            simplifiable: self.simplifiable,
+            complex_format,
+        }
+    }
+
+    pub(crate) fn base(&self) -> Base {
+        self.base
+    }
+
     pub(crate) fn with_base(self, base: Base) -> Self {
         Self {
             value: self.value,
             unit: self.unit,
             exact: self.exact,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
             base,
         }
@@ -128,6 +222,7 @@ impl Value {
             exact: self.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -141,12 +236,31 @@ impl Value {
             exact: true,
             base: Base::default(),
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
 
-    pub(crate) fn add<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn add<I: Interrupt>(self, mut rhs: Self, int: &I) -> Result<Self, FendError> {
+        if self.unit.logarithmic_unit(int)?.is_some() {
+            return self.add_to_level(rhs, int);
+        }
+        if rhs.unit.logarithmic_unit(int)?.is_some() {
+            if self.unit.decibels_per_unit(int)?.is_some() {
+                return rhs.add_to_level(self, int);
+            }
+            rhs = rhs.convert_to(self.clone().unit_one(), int)?;
+        }
         let scale_factor = Unit::compute_scale_factor(&rhs.unit, &self.unit, int)?;
+        match (
+            self.unit.is_absolute_temperature(int)?,
+            rhs.unit.is_absolute_temperature(int)?,
+        ) {
+            (true, true) => return Err(FendError::AbsoluteTemperatureAddition),
+            // a temperature difference plus an absolute temperature
+            (false, true) => return rhs.add(self, int),
+            _ => (),
+        }
         let scaled = Exact::new(rhs.value, rhs.exact)
             .mul(&scale_factor.scale_1.apply(Dist::from), int)?
             .div(&scale_factor.scale_2.apply(Dist::from), int)?;
@@ -158,6 +272,7 @@ impl Value {
             exact: self.exact && rhs.exact && value.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -183,9 +298,18 @@ impl Value {
     }
 
     pub(crate) fn convert_to<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
-        if rhs.value.one_point()? != 1.into() {
+        if rhs.value.one_point_ref()? != &1.into() {
             return Err(FendError::ConversionRhsNumerical);
         }
+        match (
+            self.unit.logarithmic_unit(int)?,
+            rhs.unit.logarithmic_unit(int)?,
+        ) {
+            (Some(a), Some(b)) if a.0 == b.0 => (),
+            (Some(log), _) => return self.logarithmic_to_linear(log, int)?.convert_to(rhs, int),
+            (None, Some(log)) => return self.into_logarithmic(log, rhs.unit, int),
+            (None, None) => (),
+        }
         let scale_factor = Unit::compute_scale_factor(&self.unit, &rhs.unit, int)?;
         let new_value = Exact::new(self.value, self.exact)
             .mul(&scale_factor.scale_1.apply(Dist::from), int)?
@@ -197,12 +321,283 @@ impl Value {
             exact: self.exact && rhs.exact && new_value.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
+            simplifiable: false,
+        })
+    }
+
+    /// Returns whether converting to `rhs` requires inverting the value
+    /// first, e.g. from `mpg` to `L / 100 km` or from `Hz` to `s`
+    pub(crate) fn converts_to_reciprocal<I: Interrupt>(
+        &self,
+        rhs: &Self,
+        int: &I,
+    ) -> Result<bool, FendError> {
+        self.unit.is_reciprocal_of(&rhs.unit, int)
+    }
+
+    /// Inverts this value and converts it to `rhs`, e.g. from `mpg` to
+    /// `L / 100 km`
+    pub(crate) fn convert_reciprocal_to<I: Interrupt>(
+        self,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let one = Self {
+            value: Dist::from(1),
+            unit: Unit::unitless(),
+            exact: true,
+            ..self.clone()
+        };
+        one.div(self, int)?.convert_to(rhs, int)
+    }
+
+    /// Converts a level like `30 dBm` into the quantity it represents,
+    /// e.g. `1 W` (in base units)
+    fn logarithmic_to_linear<I: Interrupt>(
+        self,
+        log: &LogarithmicUnit,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let &(_, factor, (num, den), base_units) = log;
+        let level = Self {
+            unit: Unit::unitless(),
+            ..self
+        };
+        let linear = level
+            .clone()
+            .decibels_to_ratio(factor, int)?
+            .mul(Self::from(num), int)?
+            .div(Self::from(den), int)?;
+        Ok(Self {
+            value: linear.value,
+            unit: Unit::from_base_units(base_units),
+            exact: linear.exact,
+            ..level
+        })
+    }
+
+    /// Converts a quantity like `1 W` into a level in the given logarithmic
+    /// unit, e.g. `30 dBm`
+    fn into_logarithmic<I: Interrupt>(
+        self,
+        log: &LogarithmicUnit,
+        unit: Unit,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let &(_, factor, (num, den), base_units) = log;
+        let reference = Self {
+            value: Dist::from(1),
+            unit: Unit::from_base_units(base_units),
+            exact: true,
+            ..self.clone()
+        };
+        let ratio = Self {
+            unit: Unit::unitless(),
+            ..self.clone().convert_to(reference, int)?
+        }
+        .mul(Self::from(den), int)?
+        .div(Self::from(num), int)?;
+        let level = ratio.log10(int)?.mul(Self::from(factor), int)?;
+        Ok(Self {
+            value: level.value,
+            unit,
+            exact: level.exact,
             simplifiable: false,
+            ..self
+        })
+    }
+
+    /// Adds to a level like `30 dBm`: adding a ratio like `10 dB` changes
+    /// the level by that amount, while adding another level adds the powers
+    /// they represent
+    fn add_to_level<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        if let Some(decibels_per_unit) = rhs.unit.decibels_per_unit(int)? {
+            let decibels = Self {
+                unit: Unit::unitless(),
+                ..rhs
+            }
+            .mul(decibels_per_unit, int)?;
+            let level = Self {
+                unit: Unit::unitless(),
+                ..self.clone()
+            }
+            .add(decibels, int)?;
+            return Ok(Self {
+                value: level.value,
+                exact: level.exact,
+                ..self
+            });
+        }
+        let rhs = rhs.convert_to(self.clone().unit_one(), int)?;
+        let sum = Self::power_sum(
+            Self {
+                unit: Unit::unitless(),
+                ..self.clone()
+            },
+            Self {
+                unit: Unit::unitless(),
+                ..rhs
+            },
+            int,
+        )?;
+        Ok(Self {
+            value: sum.value,
+            exact: sum.exact,
+            ..self
+        })
+    }
+
+    /// Subtracts from a level like `30 dBm`: subtracting a ratio like `10 dB`
+    /// changes the level by that amount, while subtracting another level
+    /// returns the ratio between them in decibels
+    fn subtract_from_level<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        if rhs.unit.decibels_per_unit(int)?.is_some() {
+            return self.add_to_level(-rhs, int);
+        }
+        let rhs = rhs.convert_to(self.clone().unit_one(), int)?;
+        let difference =
+            Exact::new(self.value, self.exact).add(&-Exact::new(rhs.value, rhs.exact), int)?;
+        let mut base_units = HashMap::new();
+        base_units.insert(BaseUnit::new_static("neper"), 1.into());
+        let decibel = NamedUnit::new(
+            Cow::Borrowed(""),
+            Cow::Borrowed("dB"),
+            Cow::Borrowed("dB"),
+            base_units,
+            Exact::new(Complex::from(10).ln(int)?, false)
+                .div(Exact::new(Complex::from(20), true), int)?
+                .value,
+        );
+        Ok(Self {
+            value: difference.value,
+            unit: Unit {
+                components: vec![UnitExponent::new(decibel, 1)],
+            },
+            exact: difference.exact,
+            base: self.base,
+            format: self.format,
+            complex_format: self.complex_format,
+            simplifiable: self.simplifiable,
+        })
+    }
+
+    /// Multiplies or divides two values. Levels like `30 dBm` are replaced
+    /// by the quantity they represent, and the result is converted back into
+    /// a level if it has the same dimension, e.g. `2 * 30 dBm` doubles the
+    /// power to `approx. 33.01 dBm`.
+    fn mul_or_div<I: Interrupt>(self, op: Bop, rhs: Self, int: &I) -> Result<Self, FendError> {
+        let lhs_log = self.unit.logarithmic_unit(int)?;
+        let rhs_log = rhs.unit.logarithmic_unit(int)?;
+        let (log, unit) = match (lhs_log, rhs_log) {
+            (Some(log), _) => (log, self.unit.clone()),
+            (None, Some(log)) => (log, rhs.unit.clone()),
+            (None, None) if op == Bop::Mul => return self.mul(rhs, int),
+            (None, None) => return self.div(rhs, int),
+        };
+        let lhs = match lhs_log {
+            Some(log) => self.logarithmic_to_linear(log, int)?,
+            None => self,
+        };
+        let rhs = match rhs_log {
+            Some(log) => rhs.logarithmic_to_linear(log, int)?,
+            None => rhs,
+        };
+        let res = match op {
+            Bop::Mul => lhs.mul(rhs, int)?,
+            _ => lhs.div(rhs, int)?,
+        };
+        if res.unit.has_base_units(log.3, int)? {
+            return res.into_logarithmic(log, unit, int);
+        }
+        Ok(res)
+    }
+
+    /// Returns `10^(self / divisor)`. Exponents that aren't integers are
+    /// approximated, since exact roots of large fractions are too slow.
+    fn decibels_to_ratio<I: Interrupt>(self, divisor: u64, int: &I) -> Result<Self, FendError> {
+        let exponent = self.div(Self::from(divisor), int)?;
+        if exponent.clone().try_as_integer(int).is_ok() {
+            return Self::from(10).pow(exponent, int);
+        }
+        let ratio = 10_f64.powf(exponent.try_as_f64(int)?);
+        Ok(Self {
+            value: Dist::from(Complex::from(Real::from(BigRat::from_f64(ratio, int)?))),
+            exact: false,
+            ..Self::from(1)
         })
     }
 
-    pub(crate) fn sub<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+    /// Adds two unitless decibel values by adding the powers they represent,
+    /// i.e. `10 log10(10^(a/10) + 10^(b/10))`
+    fn power_sum<I: Interrupt>(a: Self, b: Self, int: &I) -> Result<Self, FendError> {
+        a.decibels_to_ratio(10, int)?
+            .add(b.decibels_to_ratio(10, int)?, int)?
+            .log10(int)?
+            .mul(Self::from(10), int)
+    }
+
+    /// Splits this value into whole numbers of each unit but the last, which
+    /// receives the remaining fractional part, e.g. `5.75 ft` into `5 ft 9 in`
+    pub(crate) fn convert_to_compound<I: Interrupt>(
+        self,
+        units: Vec<Self>,
+        int: &I,
+    ) -> Result<Vec<Self>, FendError> {
+        let negative = self.value.one_point_ref()? < &Complex::from(0);
+        let mut remaining = if negative { -self } else { self };
+        let mut parts = Vec::with_capacity(units.len());
+        let last = units.len().saturating_sub(1);
+        for (i, unit) in units.into_iter().enumerate() {
+            let converted = remaining.convert_to(unit, int)?;
+            if i == last {
+                remaining = converted;
+                break;
+            }
+            let whole = converted.clone().truncate(int)?;
+            remaining = converted.sub(whole.clone(), int)?;
+            if !whole.is_zero() {
+                parts.push(whole);
+            }
+        }
+        if parts.is_empty() || !remaining.is_zero() {
+            parts.push(remaining);
+        }
+        if negative {
+            // only the leading part is negated, e.g. `-5 ft 9 inches`
+            parts[0] = -parts[0].clone();
+        }
+        Ok(parts)
+    }
+
+    fn truncate<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            value: Dist::from(self.value.one_point()?.truncate(int)?),
+            unit: self.unit,
+            exact: self.exact,
+            base: self.base,
+            format: self.format,
+            complex_format: self.complex_format,
+            simplifiable: self.simplifiable,
+        })
+    }
+
+    pub(crate) fn sub<I: Interrupt>(self, mut rhs: Self, int: &I) -> Result<Self, FendError> {
+        if self.unit.logarithmic_unit(int)?.is_some() {
+            return self.subtract_from_level(rhs, int);
+        }
+        if rhs.unit.logarithmic_unit(int)?.is_some() {
+            rhs = rhs.convert_to(self.clone().unit_one(), int)?;
+        }
         let scale_factor = Unit::compute_scale_factor(&rhs.unit, &self.unit, int)?;
+        match (
+            self.unit.is_absolute_temperature(int)?,
+            rhs.unit.is_absolute_temperature(int)?,
+        ) {
+            (true, true) => return self.temperature_difference(rhs, int),
+            (false, true) => return Err(FendError::AbsoluteTemperatureSubtraction),
+            _ => (),
+        }
         let scaled = Exact::new(rhs.value, rhs.exact)
             .mul(&scale_factor.scale_1.apply(Dist::from), int)?
             .div(&scale_factor.scale_2.apply(Dist::from), int)?;
@@ -213,6 +608,44 @@ impl Value {
             exact: self.exact && rhs.exact && value.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
+            simplifiable: self.simplifiable,
+        })
+    }
+
+    /// Subtracts two absolute temperatures, e.g. `20 °C - 59 °F` is `5 Δ°C`
+    fn temperature_difference<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        let rhs = rhs.convert_to(self.clone().unit_one(), int)?;
+        let difference =
+            Exact::new(self.value, self.exact).add(&-Exact::new(rhs.value, rhs.exact), int)?;
+        let (hashmap, scale) = self.unit.to_hashmap_and_scale(int)?;
+        let (name, delta_scale) = if hashmap.contains_key(&BaseUnit::new_static("celsius")) {
+            ("\u{394}\u{b0}C", Exact::new(Complex::from(1), true))
+        } else {
+            (
+                "\u{394}\u{b0}F",
+                Exact::new(Complex::from(5), true).div(Exact::new(Complex::from(9), true), int)?,
+            )
+        };
+        let value = difference.mul(&scale.div(delta_scale.clone(), int)?.apply(Dist::from), int)?;
+        let mut base_units = HashMap::new();
+        base_units.insert(BaseUnit::new_static("kelvin"), 1.into());
+        let unit = NamedUnit::new(
+            Cow::Borrowed(""),
+            Cow::Borrowed(name),
+            Cow::Borrowed(name),
+            base_units,
+            delta_scale.value,
+        );
+        Ok(Self {
+            value: value.value,
+            unit: Unit {
+                components: vec![UnitExponent::new(unit, 1)],
+            },
+            exact: self.exact && rhs.exact && value.exact,
+            base: self.base,
+            format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -233,6 +666,7 @@ impl Value {
             exact: value.exact && self.exact && rhs.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -251,6 +685,7 @@ impl Value {
             exact: self.exact && rhs.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -269,6 +704,7 @@ impl Value {
             exact: self.exact && rhs.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -287,15 +723,15 @@ impl Value {
                 self.add(rhs, int)
             }
             Bop::Minus => self.sub(rhs, int),
-            Bop::Mul => self.mul(rhs, int),
-            Bop::Div => self.div(rhs, int),
+            Bop::Mul | Bop::Div => self.mul_or_div(op, rhs, int),
             Bop::Mod => self.modulo(rhs, int),
             Bop::Pow => self.pow(rhs, int),
             Bop::Bitwise(bitwise_bop) => self.bitwise(rhs, bitwise_bop, int),
+            Bop::Polar => self.polar(rhs, context, int),
         }
     }
 
-    fn is_unitless<I: Interrupt>(&self, int: &I) -> Result<bool, FendError> {
+    pub(crate) fn is_unitless<I: Interrupt>(&self, int: &I) -> Result<bool, FendError> {
         // todo this is broken for unitless components
         if self.unit.components.is_empty() {
             return Ok(true);
@@ -315,6 +751,7 @@ impl Value {
         if !rhs.is_unitless(int)? {
             return Err(FendError::ExpUnitless);
         }
+        let approximate = self.approximate_pow(&rhs, int)?;
         let mut new_components = vec![];
         let mut exact_res = true;
         for unit_exp in self.unit.components {
@@ -329,17 +766,52 @@ impl Value {
         let new_unit = Unit {
             components: new_components,
         };
-        let value = self.value.one_point()?.pow(rhs.value.one_point()?, int)?;
+        let value = match approximate {
+            Some(value) => Exact::new(value, false),
+            None => self.value.one_point()?.pow(rhs.value.one_point()?, int)?,
+        };
         Ok(Self {
             value: value.value.into(),
             unit: new_unit,
             exact: self.exact && rhs.exact && exact_res && value.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
 
+    /// Raises an approximate positive number to a real power using
+    /// floating-point arithmetic, since exact powers of long fractions (e.g.
+    /// for `e^100`) are very slow to compute. Returns `None` if the result
+    /// needs to be computed exactly.
+    fn approximate_pow<I: Interrupt>(
+        &self,
+        rhs: &Self,
+        int: &I,
+    ) -> Result<Option<Complex>, FendError> {
+        if self.exact && rhs.exact {
+            return Ok(None);
+        }
+        let (Ok(base), Ok(exponent)) = (self.value.one_point_ref(), rhs.value.one_point_ref())
+        else {
+            return Ok(None);
+        };
+        let (Ok(base), Ok(exponent)) = (
+            base.clone().try_as_f64(int),
+            exponent.clone().try_as_f64(int),
+        ) else {
+            return Ok(None);
+        };
+        let result = base.powf(exponent);
+        if base <= 0.0 || !result.is_finite() {
+            return Ok(None);
+        }
+        Ok(Some(Complex::from(Real::from(BigRat::from_f64_exact(
+            result, int,
+        )?))))
+    }
+
     pub(crate) fn i() -> Self {
         Self {
             value: Complex::i().into(),
@@ -347,6 +819,7 @@ impl Value {
             exact: true,
             base: Base::default(),
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
@@ -358,6 +831,7 @@ impl Value {
             exact: true,
             base: Base::default(),
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
@@ -370,10 +844,74 @@ impl Value {
             exact: self.exact && value.exact,
             base: self.base,
             format: self.format,
+            complex_format: ComplexFormat::Cartesian,
             simplifiable: self.simplifiable,
         })
     }
 
+    /// Returns the angle of a complex number in radians
+    pub(crate) fn arg<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        let value = self.value.one_point()?.arg(int)?;
+        Ok(Self {
+            value: value.value.into(),
+            unit: Unit::unitless(),
+            exact: self.exact && value.exact,
+            base: self.base,
+            format: self.format,
+            complex_format: ComplexFormat::Cartesian,
+            simplifiable: self.simplifiable,
+        })
+    }
+
+    /// Creates a complex number from a magnitude (`self`) and an angle,
+    /// e.g. `5 ∠ 30°`. Unitless angles are interpreted as radians.
+    pub(crate) fn polar<I: Interrupt>(
+        self,
+        angle: Self,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if !angle.is_unitless(int)?
+            && angle
+                .clone()
+                .convert_angle_to_rad(None, context, int)
+                .is_err()
+        {
+            return Err(FendError::ExpectedAnAngle);
+        }
+        let cos = angle.clone().cos(None, context, int)?;
+        let sin = angle.sin(None, context, int)?;
+        let direction = cos.add(Self::i().mul(sin, int)?, int)?;
+        Ok(self
+            .mul(direction, int)?
+            .with_complex_format(ComplexFormat::Polar))
+    }
+
+    /// Converts a finite float into the exact number it represents
+    pub(crate) fn from_f64<I: Interrupt>(value: f64, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            value: Dist::from(Complex::from(Real::from(BigRat::from_f64_exact(
+                value, int,
+            )?))),
+            ..Self::from(1)
+        })
+    }
+
+    /// Converts an approximate float, rounding it to 12 significant figures
+    pub(crate) fn from_f64_rounded<I: Interrupt>(value: f64, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            value: Dist::from(Complex::from(Real::from(BigRat::from_f64_rounded(
+                value, int,
+            )?))),
+            exact: false,
+            ..Self::from(1)
+        })
+    }
+
+    pub(crate) fn is_exact(&self) -> bool {
+        self.exact
+    }
+
     pub(crate) fn make_approximate(self) -> Self {
         Self {
             value: self.value,
@@ -381,6 +919,7 @@ impl Value {
             exact: false,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         }
     }
@@ -392,6 +931,7 @@ impl Value {
             exact: true,
             base,
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
@@ -424,6 +964,7 @@ impl Value {
             exact: self.exact && exact.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -443,6 +984,7 @@ impl Value {
             exact: false,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -476,6 +1018,7 @@ impl Value {
             exact: true,
             base: Base::default(),
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
@@ -588,19 +1131,48 @@ impl Value {
         } else {
             UseParentheses::IfComplex
         };
+        let currency = self.currency_format();
         let mut formatted_value = String::new();
-        let mut exact = self
-            .value
-            .format(
-                self.exact,
-                self.format,
-                self.base,
-                use_parentheses,
+        let mut exact = match (self.complex_format, self.value.one_point_ref(), currency) {
+            (ComplexFormat::Polar, Ok(value), _) => self.format_polar(
+                value,
+                use_parentheses != UseParentheses::No,
                 &mut formatted_value,
-                ctx,
                 int,
-            )?
-            .exact;
+            )?,
+            (
+                ComplexFormat::Cartesian,
+                Ok(value),
+                Some(CurrencyFormat {
+                    minor_digits: Some(minor_digits),
+                    ..
+                }),
+            ) if self.format == FormattingStyle::Auto
+                && self.base == Base::default()
+                && value.is_real() =>
+            {
+                let rounded = value.clone().round_to_decimal_places(
+                    minor_digits,
+                    ctx.bankers_rounding,
+                    int,
+                )?;
+                format_currency_amount(&rounded, minor_digits, &mut formatted_value, int)?;
+                self.exact && rounded.exact
+            }
+            _ => {
+                self.value
+                    .format(
+                        self.exact,
+                        self.format,
+                        self.base,
+                        use_parentheses,
+                        &mut formatted_value,
+                        ctx,
+                        int,
+                    )?
+                    .exact
+            }
+        };
         let unit_string = self.unit.format(
             "",
             self.value.equals_int(1),
@@ -610,13 +1182,89 @@ impl Value {
             int,
         )?;
         exact = exact && unit_string.exact;
+        let mut unit_str = unit_string.value;
+        let mut unit_first = false;
+        if let Some(currency) = currency.filter(|currency| currency.is_symbol) {
+            formatted_value = formatted_value.replace('.', &currency.decimal_separator.to_string());
+            unit_first = currency.symbol_first;
+            if !unit_first && !unit_str.starts_with(' ') {
+                unit_str.insert(0, ' ');
+            }
+        }
         Ok(FormattedValue {
             number: formatted_value,
             exact,
-            unit_str: unit_string.value,
+            unit_str,
+            unit_first,
         })
     }
 
+    /// Formats the unit of this value in terms of base units, e.g.
+    /// `kilogram meter second^-2` for newtons
+    pub(crate) fn base_units<I: Interrupt>(&self, int: &I) -> Result<String, FendError> {
+        let (hashmap, _) = self.unit.to_hashmap_and_scale(int)?;
+        let (hashmap, _, _) = Unit::reduce_hashmap(hashmap, int)?;
+        Unit::print_base_units(hashmap, int)
+    }
+
+    /// Returns how this value should be printed if its unit is a single
+    /// currency, e.g. `USD` or `$`
+    fn currency_format(&self) -> Option<CurrencyFormat> {
+        match self.unit.components.as_slice() {
+            [UnitExponent { unit, exponent }] if *exponent == 1.into() => {
+                match unit.prefix_and_name(false) {
+                    ("", name) => query_currency_format(name),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Formats a complex number as its magnitude and angle in degrees,
+    /// e.g. `5 ∠ 30°`. Returns whether the output is exact.
+    fn format_polar<I: Interrupt>(
+        &self,
+        value: &Complex,
+        use_parentheses: bool,
+        out: &mut String,
+        int: &I,
+    ) -> Result<bool, FendError> {
+        let mut magnitude = value.clone().abs(int)?;
+        let mut angle = value
+            .clone()
+            .arg(int)?
+            .mul(&Exact::new(180.into(), true), int)?
+            .div(Exact::new(Complex::pi(), true), int)?;
+        // avoid printing e.g. `5 ∠ 29.9999999999°` for `5 ∠ 30°`
+        for part in [&mut magnitude, &mut angle] {
+            part.exact = self.exact && part.exact;
+            if !part.exact {
+                let rounded = BigRat::from_f64_rounded(part.value.clone().try_as_f64(int)?, int)?;
+                part.value = Complex::from(Real::from(rounded));
+            }
+        }
+        let magnitude = magnitude.value.format(
+            magnitude.exact,
+            self.format,
+            self.base,
+            UseParentheses::No,
+            int,
+        )?;
+        let angle =
+            angle
+                .value
+                .format(angle.exact, self.format, self.base, UseParentheses::No, int)?;
+        if use_parentheses {
+            out.push('(');
+        }
+        write!(out, "{} \u{2220} {}\u{b0}", magnitude.value, angle.value)?;
+        if use_parentheses {
+            out.push(')');
+        }
+        Ok(magnitude.exact && angle.exact)
+    }
+
     pub(crate) fn mul<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
         let components = [self.unit.components, rhs.unit.components].concat();
         let value =
@@ -627,6 +1275,7 @@ impl Value {
             exact: self.exact && rhs.exact && value.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
@@ -760,10 +1409,99 @@ impl Value {
             exact: res_exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         })
     }
 
+    /// Converts the value into a named SI derived unit if there is one with
+    /// the same dimension, e.g. `2 kW * 3 h` becomes `21600000 joules`
+    pub(crate) fn into_simplest_unit<I: Interrupt>(
+        self,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let simplified = Self {
+            simplifiable: true,
+            ..self
+        }
+        .simplify(int)?;
+        let (hashmap, scale) = simplified.unit.to_hashmap_and_scale(int)?;
+        if simplified.unit.components.len() == 1 && scale.value == 1.into() {
+            // already a coherent named unit
+            return Ok(simplified);
+        }
+        match Unit::derived_unit(&hashmap, context, int)? {
+            Some(unit) => simplified.convert_to(Self::new(1, unit.components), int),
+            None => Ok(simplified),
+        }
+    }
+
+    /// Returns whether the unit of this value is made up of more than one
+    /// unit, like `mph h` or `lb ft / s^2`
+    pub(crate) fn has_compound_unit(&self) -> bool {
+        self.unit.components.len() > 1
+    }
+
+    /// Returns whether this value has the given dimension, e.g.
+    /// `[("meter", 1), ("second", -1)]` for a speed
+    pub(crate) fn has_base_units<I: Interrupt>(
+        &self,
+        base_units: &[(&'static str, i64)],
+        int: &I,
+    ) -> Result<bool, FendError> {
+        self.unit.has_base_units(base_units, int)
+    }
+
+    /// Returns whether this value only uses SI base units like meters and
+    /// seconds, and not e.g. bits or currencies
+    pub(crate) fn has_si_base_units<I: Interrupt>(&self, int: &I) -> Result<bool, FendError> {
+        const SI_BASE_UNITS: &[&str] = &[
+            "meter", "kilogram", "second", "ampere", "kelvin", "mole", "candela",
+        ];
+        let (hashmap, _) = self.unit.to_hashmap_and_scale(int)?;
+        Ok(hashmap
+            .keys()
+            .all(|base_unit| SI_BASE_UNITS.contains(&base_unit.name())))
+    }
+
+    /// Returns the prefix and singular name of the first unit in this value,
+    /// along with its exponent if that is a positive integer
+    pub(crate) fn leading_unit<I: Interrupt>(
+        &self,
+        int: &I,
+    ) -> Result<Option<(String, String, usize)>, FendError> {
+        let Some(first) = self.unit.components.first() else {
+            return Ok(None);
+        };
+        let exponent = match first.exponent.clone().try_as_usize(int) {
+            Ok(exponent) if exponent > 0 => exponent,
+            Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+            _ => return Ok(None),
+        };
+        let (prefix, name) = first.unit.prefix_and_name(false);
+        Ok(Some((prefix.to_string(), name.to_string(), exponent)))
+    }
+
+    /// Converts the value so that its first unit is replaced by the unit of
+    /// `unit`, keeping its exponent and any other units, e.g. to turn
+    /// `1500000000 Hz` into `1.5 GHz`
+    pub(crate) fn convert_leading_unit_to<I: Interrupt>(
+        self,
+        unit: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut components = self.unit.components.clone();
+        match (
+            components.first_mut(),
+            unit.unit.components.into_iter().next(),
+        ) {
+            (Some(first), Some(replacement)) => first.unit = replacement.unit,
+            _ => return Ok(self),
+        }
+        self.convert_to(Self::new(1, components), int)
+    }
+
     pub(crate) fn unit_equal_to(&self, rhs: &str) -> bool {
         self.unit.equal_to(rhs)
     }
@@ -778,6 +1516,7 @@ impl Neg for Value {
             exact: self.exact,
             base: self.base,
             format: self.format,
+            complex_format: self.complex_format,
             simplifiable: self.simplifiable,
         }
     }
@@ -791,6 +1530,21 @@ impl From<u64> for Value {
             exact: true,
             base: Base::default(),
             format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
+            simplifiable: true,
+        }
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(i: BigUint) -> Self {
+        Self {
+            value: Complex::from(Real::from(BigRat::from(i))).into(),
+            unit: Unit::unitless(),
+            exact: true,
+            base: Base::default(),
+            format: FormattingStyle::default(),
+            complex_format: ComplexFormat::default(),
             simplifiable: true,
         }
     }
@@ -816,9 +1570,22 @@ pub(crate) struct FormattedValue {
     exact: bool,
     number: String,
     unit_str: String,
+    unit_first: bool,
 }
 
 impl FormattedValue {
+    pub(crate) fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Formats this value without an "approx." prefix
+    pub(crate) fn into_exact(self) -> Self {
+        Self {
+            exact: true,
+            ..self
+        }
+    }
+
     pub(crate) fn spans(self, spans: &mut Vec<Span>) {
         if !self.exact {
             spans.push(Span {
@@ -826,7 +1593,7 @@ impl FormattedValue {
                 kind: SpanKind::Ident,
             });
         }
-        if self.unit_str == "$" || self.unit_str == "\u{a3}" {
+        if self.unit_first {
             spans.push(Span {
                 string: self.unit_str,
                 kind: SpanKind::Ident,
@@ -853,11 +1620,58 @@ impl fmt::Display for FormattedValue {
         if !self.exact {
             write!(f, "approx. ")?;
         }
-        write!(f, "{}{}", self.number, self.unit_str)?;
+        if self.unit_first {
+            write!(f, "{}{}", self.unit_str, self.number)?;
+        } else {
+            write!(f, "{}{}", self.number, self.unit_str)?;
+        }
         Ok(())
     }
 }
 
+/// Writes a currency amount that has already been rounded to its minor unit,
+/// padding it with zeroes where necessary (e.g. `3.5` becomes `3.50`).
+/// Exact integers like `5` are printed without any decimal places.
+fn format_currency_amount<I: Interrupt>(
+    amount: &Exact<Complex>,
+    minor_digits: usize,
+    out: &mut String,
+    int: &I,
+) -> Result<(), FendError> {
+    let formatted = amount
+        .value
+        .format(
+            true,
+            FormattingStyle::DecimalPlaces(minor_digits),
+            Base::default(),
+            UseParentheses::No,
+            int,
+        )?
+        .value
+        .to_string();
+    out.push_str(&formatted);
+    let decimals = match formatted.split_once('.') {
+        Some((_, decimals)) => decimals.len(),
+        None if !amount.exact && minor_digits > 0 => {
+            out.push('.');
+            0
+        }
+        None => return Ok(()),
+    };
+    for _ in decimals..minor_digits {
+        out.push('0');
+    }
+    Ok(())
+}
+
+fn exponent_from_i64(exp: i64) -> Complex {
+    if exp < 0 {
+        -Complex::from(exp.unsigned_abs())
+    } else {
+        Complex::from(exp.unsigned_abs())
+    }
+}
+
 #[derive(Clone)]
 struct Unit {
     components: Vec<UnitExponent>,
@@ -872,6 +1686,60 @@ struct ScaleFactor {
     scale_2: Exact<Complex>,
 }
 
+// logarithmic units of a level relative to a reference quantity, e.g.
+// `30 dBm` is `10 log10(1 W / 1 mW)`: name, factor in front of the logarithm
+// (10 for power and 20 for root-power quantities), reference as a fraction
+// and the base units of the reference
+type LogarithmicUnit = (
+    &'static str,
+    u64,
+    (u64, u64),
+    &'static [(&'static str, i64)],
+);
+
+const LOGARITHMIC_UNITS: &[LogarithmicUnit] = &[
+    (
+        "dBm",
+        10,
+        (1, 1000),
+        &[("kilogram", 1), ("meter", 2), ("second", -3)],
+    ),
+    (
+        "dBW",
+        10,
+        (1, 1),
+        &[("kilogram", 1), ("meter", 2), ("second", -3)],
+    ),
+    (
+        "dBV",
+        20,
+        (1, 1),
+        &[
+            ("kilogram", 1),
+            ("meter", 2),
+            ("second", -3),
+            ("ampere", -1),
+        ],
+    ),
+    (
+        "dB\u{b5}V",
+        20,
+        (1, 1_000_000),
+        &[
+            ("kilogram", 1),
+            ("meter", 2),
+            ("second", -3),
+            ("ampere", -1),
+        ],
+    ),
+    (
+        "dB_SPL",
+        20,
+        (1, 50_000),
+        &[("kilogram", 1), ("meter", -1), ("second", -2)],
+    ),
+];
+
 impl Unit {
     pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
         serialize_usize(self.components.len(), write)?;
@@ -902,6 +1770,41 @@ impl Unit {
         prefix.is_empty() && name == rhs
     }
 
+    fn is_reciprocal_of<I: Interrupt>(&self, other: &Self, int: &I) -> Result<bool, FendError> {
+        let (hash_a, _) = self.to_hashmap_and_scale(int)?;
+        let (hash_b, _) = other.to_hashmap_and_scale(int)?;
+        let (hash_a, _, _) = Self::reduce_hashmap(hash_a, int)?;
+        let (hash_b, _, _) = Self::reduce_hashmap(hash_b, int)?;
+        Ok(!hash_a.is_empty()
+            && hash_a.len() == hash_b.len()
+            && hash_a
+                .iter()
+                .all(|(base_unit, exp)| hash_b.get(base_unit) == Some(&-exp.clone())))
+    }
+
+    /// Returns whether this unit consists of exactly the given base units
+    fn has_base_units<I: Interrupt>(
+        &self,
+        base_units: &[(&'static str, i64)],
+        int: &I,
+    ) -> Result<bool, FendError> {
+        let (hashmap, _) = self.to_hashmap_and_scale(int)?;
+        Ok(hashmap.len() == base_units.len()
+            && base_units.iter().all(|&(name, exp)| {
+                hashmap.get(&BaseUnit::new_static(name)) == Some(&exponent_from_i64(exp))
+            }))
+    }
+
+    /// Returns whether this is a temperature on a scale with an offset,
+    /// i.e. degrees Celsius or Fahrenheit
+    fn is_absolute_temperature<I: Interrupt>(&self, int: &I) -> Result<bool, FendError> {
+        let (hashmap, _) = self.to_hashmap_and_scale(int)?;
+        Ok(hashmap.len() == 1
+            && ["celsius", "fahrenheit"]
+                .into_iter()
+                .any(|name| hashmap.get(&BaseUnit::new_static(name)) == Some(&1.into())))
+    }
+
     /// guarantees that base units with an cancelled exponents do not appear in the hashmap
     fn to_hashmap_and_scale<I: Interrupt>(&self, int: &I) -> Result<HashmapScale, FendError> {
         let mut hashmap = HashMap::<BaseUnit, Complex>::new();
@@ -965,6 +1868,8 @@ impl Unit {
         hash: HashMap<BaseUnit, Complex>,
         int: &I,
     ) -> Result<String, FendError> {
+        let mut hash: Vec<_> = hash.into_iter().collect();
+        hash.sort_by(|(a, _), (b, _)| a.name().cmp(b.name()));
         let from_base_units: Vec<_> = hash
             .into_iter()
             .map(|(base_unit, exponent)| {
@@ -1035,6 +1940,69 @@ impl Unit {
         Self { components: vec![] }
     }
 
+    fn from_base_units(base_units: &[(&'static str, i64)]) -> Self {
+        Self {
+            components: base_units
+                .iter()
+                .map(|&(name, exp)| {
+                    UnitExponent::new(
+                        NamedUnit::new_from_base(BaseUnit::new_static(name)),
+                        exponent_from_i64(exp),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the logarithmic unit (like dBm) if this unit is one
+    fn logarithmic_unit<I: Interrupt>(
+        &self,
+        int: &I,
+    ) -> Result<Option<&'static LogarithmicUnit>, FendError> {
+        let (hashmap, scale) = self.to_hashmap_and_scale(int)?;
+        if hashmap.len() != 1 || scale.value != 1.into() {
+            return Ok(None);
+        }
+        Ok(LOGARITHMIC_UNITS
+            .iter()
+            .find(|(name, _, _, _)| hashmap.get(&BaseUnit::new_static(name)) == Some(&1.into())))
+    }
+
+    /// Returns how many decibels one of this unit is, if this is a
+    /// logarithmic ratio like `dB` or `Np`
+    fn decibels_per_unit<I: Interrupt>(&self, int: &I) -> Result<Option<Value>, FendError> {
+        let (hashmap, scale) = self.to_hashmap_and_scale(int)?;
+        if hashmap.len() != 1 || hashmap.get(&BaseUnit::new_static("neper")) != Some(&1.into()) {
+            return Ok(None);
+        }
+        // one neper is a power ratio of e^2, i.e. 20 / ln(10) decibels
+        let nepers = Value {
+            value: Dist::from(scale.value),
+            exact: scale.exact,
+            ..Value::from(1)
+        };
+        Ok(Some(
+            nepers
+                .mul(Value::from(20), int)?
+                .div(Value::from(10).ln(int)?, int)?,
+        ))
+    }
+
+    /// Returns the named SI derived unit with exactly the given base units
+    fn derived_unit<I: Interrupt>(
+        hashmap: &HashMap<BaseUnit, Complex>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Option<Self>, FendError> {
+        for derived in crate::units::derived_units(context, int)? {
+            let (base_units, scale) = derived.unit.to_hashmap_and_scale(int)?;
+            if base_units == *hashmap && scale.value == 1.into() {
+                return Ok(Some(derived.unit));
+            }
+        }
+        Ok(None)
+    }
+
     fn format<I: Interrupt>(
         &self,
         unitless: &str,
@@ -0,0 +1,711 @@
+use crate::ast::Expr;
+use crate::error::{FendError, Interrupt};
+use crate::ident::Ident;
+use crate::interrupt::test_int;
+use crate::num::Number;
+use crate::scope::Scope;
+use crate::value::{ApplyMulHandling, Value};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+const MAX_ITERATIONS: usize = 200;
+const MAX_INTERVALS: usize = 200;
+const MAX_ROOTS: usize = 8;
+
+// 15-point Gauss-Kronrod nodes and weights on [-1, 1], with the
+// corresponding 7-point Gauss weights used for the error estimate
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// Calls the lambda `f` with the argument `x`, expecting a numerical result
+fn call<I: Interrupt>(
+    f: &Value,
+    x: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    match f.clone().apply(
+        Expr::Literal(Value::Num(Box::new(x))),
+        ApplyMulHandling::OnlyApply,
+        None,
+        context,
+        int,
+    )? {
+        Value::Num(n) => Ok(*n),
+        v => Err(FendError::FunctionMustReturnANumber(v.type_name())),
+    }
+}
+
+struct Equation<'a> {
+    lhs: Value,
+    rhs: Option<Value>,
+    unit: Number,
+    context: &'a mut crate::Context,
+}
+
+impl Equation<'_> {
+    /// Returns `lhs - rhs` evaluated at `x` (which is given in terms of the unknown's unit)
+    fn residual<I: Interrupt>(&mut self, x: Number, int: &I) -> Result<Number, FendError> {
+        let x = x.mul(self.unit.clone(), int)?;
+        let lhs = call(&self.lhs, x.clone(), self.context, int)?;
+        match &self.rhs {
+            Some(rhs) => lhs.sub(call(rhs, x, self.context, int)?, int),
+            None => Ok(lhs),
+        }
+    }
+
+    /// Evaluates the residual as an `f64`, returning `None` if the equation
+    /// isn't defined (or not real) at `x`
+    fn residual_f64<I: Interrupt>(&mut self, x: f64, int: &I) -> Result<Option<f64>, FendError> {
+        test_int(int)?;
+        // an approximate argument lets e.g. `2^x` be computed using
+        // floating-point arithmetic instead of exactly
+        let x = Number::from_f64(x, int)?.make_approximate();
+        match self.residual(x, int).and_then(|r| r.try_as_f64(int)) {
+            Ok(r) if r.is_finite() => Ok(Some(r)),
+            Err(FendError::Interrupted) => Err(FendError::Interrupted),
+            Ok(_) | Err(_) => Ok(None),
+        }
+    }
+
+    /// Works out which unit the unknown needs to have for both sides of the
+    /// equation to be compatible. Unitless numbers are tried first, and
+    /// otherwise the unit is derived from the ratio of both sides.
+    fn infer_unit<I: Interrupt>(&mut self, int: &I) -> Result<(), FendError> {
+        let err = match self.residual(1.into(), int) {
+            Ok(_) => return Ok(()),
+            Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+            Err(e) => e,
+        };
+        let rhs = match &self.rhs {
+            Some(rhs) => call(rhs, 1.into(), self.context, int)?,
+            None => return Err(err),
+        };
+        let ratio = call(&self.lhs, 1.into(), self.context, int)?
+            .div(rhs, int)?
+            .simplify(int)?
+            .unit_one();
+        for (num, den) in [(1, 1), (1, 2), (1, 3), (1, 4), (2, 1), (3, 1)] {
+            for sign in [1, -1] {
+                test_int(int)?;
+                let exponent = Number::from(den).div(Number::from(num), int)?;
+                let exponent = if sign < 0 { -exponent } else { exponent };
+                self.unit = ratio.clone().pow(exponent, int)?.simplify(int)?;
+                if self.residual(1.into(), int).is_ok() {
+                    return Ok(());
+                }
+            }
+        }
+        Err(err)
+    }
+
+    /// Returns the residual at `x` if it can be computed exactly
+    fn exact_residual<I: Interrupt>(
+        &mut self,
+        x: Number,
+        int: &I,
+    ) -> Result<Option<Number>, FendError> {
+        match self.residual(x, int) {
+            Ok(value) if value.is_exact() => Ok(Some(value)),
+            Err(FendError::Interrupted) => Err(FendError::Interrupted),
+            Ok(_) | Err(_) => Ok(None),
+        }
+    }
+
+    /// Checks whether the residual is a polynomial of at most second degree
+    /// by fitting it through `-1`, `0` and `1` and verifying the fit at a few
+    /// other points. If it is, returns its real roots, which are exact where
+    /// possible.
+    fn polynomial_roots<I: Interrupt>(
+        &mut self,
+        int: &I,
+    ) -> Result<Option<Vec<Number>>, FendError> {
+        let (Some(constant), Some(above), Some(below)) = (
+            self.exact_residual(0.into(), int)?,
+            self.exact_residual(1.into(), int)?,
+            self.exact_residual(-Number::from(1), int)?,
+        ) else {
+            return Ok(None);
+        };
+        let quadratic = above
+            .clone()
+            .add(below.clone(), int)?
+            .div(2.into(), int)?
+            .sub(constant.clone(), int)?;
+        let linear = above.sub(below, int)?.div(2.into(), int)?;
+        for x in [
+            Number::from(2),
+            -Number::from(3),
+            Number::from(1).div(2.into(), int)?,
+            10.into(),
+        ] {
+            let expected = quadratic
+                .clone()
+                .mul(x.clone().mul(x.clone(), int)?, int)?
+                .add(linear.clone().mul(x.clone(), int)?, int)?
+                .add(constant.clone(), int)?;
+            let Some(value) = self.exact_residual(x, int)? else {
+                return Ok(None);
+            };
+            if !value.sub(expected, int)?.is_zero() {
+                return Ok(None);
+            }
+        }
+        if quadratic.is_zero() {
+            if linear.is_zero() {
+                return Ok(None);
+            }
+            let root = (-constant).div(linear, int)?.simplify(int)?;
+            return Ok(Some(vec![root]));
+        }
+        // the roots of x^2 + px + q are -p/2 ± sqrt(p^2/4 - q)
+        let p = linear.div(quadratic.clone(), int)?.simplify(int)?;
+        let q = constant.div(quadratic, int)?.simplify(int)?;
+        let vertex = (-p).div(2.into(), int)?;
+        let discriminant = vertex.clone().mul(vertex.clone(), int)?.sub(q, int)?;
+        if discriminant.is_zero() {
+            return Ok(Some(vec![vertex]));
+        }
+        if discriminant.clone().try_as_f64(int)? < 0.0 {
+            return Ok(Some(vec![]));
+        }
+        let mut offset = discriminant
+            .clone()
+            .pow(Number::from(1).div(2.into(), int)?, int)?;
+        if !offset.is_exact() {
+            offset = Number::from_f64_rounded(discriminant.try_as_f64(int)?.sqrt(), int)?;
+        }
+        Ok(Some(vec![
+            vertex.clone().sub(offset.clone(), int)?,
+            vertex.add(offset, int)?,
+        ]))
+    }
+
+    /// Narrows down a sign change of the residual between `a` and `b` using
+    /// secant (Newton-like) steps, falling back to bisection whenever a step
+    /// leaves the bracket or stops making progress
+    fn refine<I: Interrupt>(
+        &mut self,
+        mut a: f64,
+        mut b: f64,
+        mut fa: f64,
+        mut fb: f64,
+        int: &I,
+    ) -> Result<Option<f64>, FendError> {
+        let scale = fa.abs().max(fb.abs());
+        for i in 0..MAX_ITERATIONS {
+            let secant = b - fb * (b - a) / (fb - fa);
+            let x = if i % 2 == 0 && secant > a && secant < b {
+                secant
+            } else {
+                a + (b - a) / 2.0
+            };
+            if x <= a || x >= b {
+                break;
+            }
+            let Some(fx) = self.residual_f64(x, int)? else {
+                return Ok(None);
+            };
+            if fx == 0.0 {
+                return Ok(Some(x));
+            }
+            if fx.signum() == fa.signum() {
+                a = x;
+                fa = fx;
+            } else {
+                b = x;
+                fb = fx;
+            }
+        }
+        let (x, fx) = if fa.abs() < fb.abs() {
+            (a, fa)
+        } else {
+            (b, fb)
+        };
+        // a sign change across a pole (e.g. 1/x) isn't a root
+        if fx.abs() > scale * 1e-6 {
+            return Ok(None);
+        }
+        Ok(Some(x))
+    }
+
+    /// Looks for the minimum of `|f|` between `a` and `b` using a
+    /// golden-section search, where `f` is positive (or negative) at both ends.
+    /// Returns the position of the minimum and the residual there.
+    fn minimize<I: Interrupt>(
+        &mut self,
+        mut a: f64,
+        mut b: f64,
+        positive: bool,
+        int: &I,
+    ) -> Result<Option<(f64, f64)>, FendError> {
+        let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+        let mut best = None;
+        for _ in 0..MAX_ITERATIONS {
+            let c = b - (b - a) * ratio;
+            let d = a + (b - a) * ratio;
+            if c <= a || d >= b || c >= d {
+                break;
+            }
+            let (Some(fc), Some(fd)) = (self.residual_f64(c, int)?, self.residual_f64(d, int)?)
+            else {
+                return Ok(None);
+            };
+            for (x, fx) in [(c, fc), (d, fd)] {
+                if (fx > 0.0) != positive {
+                    // the residual crosses zero twice
+                    return Ok(Some((x, fx)));
+                }
+            }
+            if (fc < fd) == positive {
+                b = d;
+                best = Some((c, fc));
+            } else {
+                a = c;
+                best = Some((d, fd));
+            }
+        }
+        Ok(best)
+    }
+
+    /// Evaluates the residual at points of increasing magnitude in the
+    /// direction of `sign`, stopping once the residual has grown far beyond
+    /// the smallest value seen so far (which includes `f0`). Otherwise e.g.
+    /// `2^x` would need to be evaluated at huge `x`.
+    fn scan<I: Interrupt>(
+        &mut self,
+        sign: f64,
+        f0: Option<f64>,
+        int: &I,
+    ) -> Result<Vec<(f64, Option<f64>)>, FendError> {
+        let mut result = vec![];
+        let mut smallest = f0.map_or(f64::INFINITY, f64::abs);
+        for exp in -6..=9 {
+            for mantissa in 1..=9 {
+                let x: f64 = format!("{mantissa}e{exp}").parse().unwrap_or_default();
+                let fx = self.residual_f64(sign * x, int)?;
+                result.push((sign * x, fx));
+                let Some(fx) = fx else {
+                    continue;
+                };
+                if fx.abs() > smallest.max(1.0) * 1e12 {
+                    return Ok(result);
+                }
+                smallest = smallest.min(fx.abs());
+            }
+        }
+        Ok(result)
+    }
+
+    fn find_roots<I: Interrupt>(&mut self, int: &I) -> Result<Vec<f64>, FendError> {
+        let f0 = self.residual_f64(0.0, int)?;
+        let mut scanned = self.scan(-1.0, f0, int)?;
+        scanned.reverse();
+        scanned.push((0.0, f0));
+        scanned.extend(self.scan(1.0, f0, int)?);
+        let (points, values): (Vec<f64>, Vec<Option<f64>>) = scanned.into_iter().unzip();
+
+        let mut roots = vec![];
+        for i in 0..points.len() {
+            if roots.len() > 2 * MAX_ROOTS {
+                return Err(FendError::TooManySolutions);
+            }
+            let Some(fx) = values[i] else {
+                continue;
+            };
+            if fx == 0.0 {
+                roots.push(points[i]);
+                continue;
+            }
+            if let Some(Some(fb)) = values.get(i + 1) {
+                if *fb != 0.0 && fx.signum() != fb.signum() {
+                    if let Some(root) = self.refine(points[i], points[i + 1], fx, *fb, int)? {
+                        roots.push(root);
+                    }
+                }
+            }
+            // a local minimum of |f| may hide a root of even multiplicity,
+            // or two roots that lie between neighbouring points
+            let (Some(Some(fa)), Some(Some(fb))) =
+                (values.get(i.wrapping_sub(1)), values.get(i + 1))
+            else {
+                continue;
+            };
+            if fa.signum() != fx.signum()
+                || fb.signum() != fx.signum()
+                || fx.abs() >= fa.abs()
+                || fx.abs() >= fb.abs()
+            {
+                continue;
+            }
+            let (a, b) = (points[i - 1], points[i + 1]);
+            let Some((x, fm)) = self.minimize(a, b, fx > 0.0, int)? else {
+                continue;
+            };
+            if fm.signum() != fx.signum() {
+                let fa = *fa;
+                let fb = *fb;
+                if let Some(root) = self.refine(a, x, fa, fm, int)? {
+                    roots.push(root);
+                }
+                if let Some(root) = self.refine(x, b, fm, fb, int)? {
+                    roots.push(root);
+                }
+            } else if fm.abs() <= 1e-10 * fa.abs().max(fb.abs()) {
+                roots.push(x);
+            }
+        }
+        roots.sort_by(f64::total_cmp);
+        roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-9 * a.abs().max(b.abs()));
+        if roots.len() > MAX_ROOTS {
+            return Err(FendError::TooManySolutions);
+        }
+        Ok(roots)
+    }
+
+    /// Turns an approximate root back into a number, preferring an exact
+    /// result if a nearby short decimal satisfies the equation exactly
+    fn make_root<I: Interrupt>(&mut self, root: f64, int: &I) -> Result<Number, FendError> {
+        for decimal_places in [0, 6] {
+            let scale = 10_f64.powi(decimal_places);
+            let x = Number::from_f64((root * scale).round(), int)?
+                .div(Number::from_f64(scale, int)?, int)?;
+            if let Ok(r) = self.residual(x.clone(), int) {
+                if r.is_zero() {
+                    return x.mul(self.unit.clone(), int);
+                }
+            }
+        }
+        Number::from_f64_rounded(root, int)?.mul(self.unit.clone(), int)
+    }
+}
+
+/// Numerically solves `lhs = rhs` (or `lhs = 0`) for the unknown `var`,
+/// returning a number if there is a single real root, or an object
+/// containing all roots that were found
+pub(crate) fn solve<I: Interrupt>(
+    var: &Ident,
+    lhs: Expr,
+    rhs: Option<Expr>,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let mut equation = Equation {
+        lhs: Value::Fn(var.clone(), Box::new(lhs), scope.clone()),
+        rhs: rhs.map(|rhs| Value::Fn(var.clone(), Box::new(rhs), scope)),
+        unit: 1.into(),
+        context,
+    };
+    equation.infer_unit(int)?;
+    let mut results = if let Some(roots) = equation.polynomial_roots(int)? {
+        let mut results = Vec::with_capacity(roots.len());
+        for root in roots {
+            results.push(root.mul(equation.unit.clone(), int)?);
+        }
+        results
+    } else {
+        let roots = equation.find_roots(int)?;
+        let mut results = Vec::with_capacity(roots.len());
+        for root in roots {
+            results.push(equation.make_root(root, int)?);
+        }
+        results
+    };
+    match results.len() {
+        0 => Err(FendError::NoSolutionFound),
+        1 => Ok(Value::Num(Box::new(results.remove(0)))),
+        _ => Ok(Value::Object(
+            results
+                .into_iter()
+                .enumerate()
+                .map(|(i, root)| {
+                    (
+                        Cow::Owned(format!("{var}_{}", i + 1)),
+                        Box::new(Value::Num(Box::new(root))),
+                    )
+                })
+                .collect(),
+        )),
+    }
+}
+
+/// Turns an `f64` back into an approximate number with the given unit,
+/// keeping 12 significant figures
+fn approximate_result<I: Interrupt>(
+    value: f64,
+    unit: Number,
+    name: &'static str,
+    int: &I,
+) -> Result<Number, FendError> {
+    if !value.is_finite() {
+        return Err(FendError::DoesNotConverge(name));
+    }
+    Number::from_f64_rounded(value, int)?.mul(unit, int)
+}
+
+struct Integrand<'a> {
+    f: &'a Value,
+    x_unit: Number,
+    y_unit: Number,
+    context: &'a mut crate::Context,
+}
+
+impl Integrand<'_> {
+    fn eval<I: Interrupt>(&mut self, x: f64, int: &I) -> Result<f64, FendError> {
+        test_int(int)?;
+        let x = Number::from_f64(x, int)?.mul(self.x_unit.clone(), int)?;
+        let y = call(self.f, x, self.context, int)?
+            .convert_to(self.y_unit.clone(), int)?
+            .try_as_f64(int)?;
+        if y.is_finite() {
+            Ok(y)
+        } else {
+            Err(FendError::DoesNotConverge("integral"))
+        }
+    }
+
+    /// Applies the 15-point Gauss-Kronrod rule to `[a, b]`, returning
+    /// the estimated integral and its estimated absolute error
+    fn gauss_kronrod<I: Interrupt>(
+        &mut self,
+        a: f64,
+        b: f64,
+        int: &I,
+    ) -> Result<(f64, f64), FendError> {
+        let center = a + (b - a) / 2.0;
+        let half_width = (b - a) / 2.0;
+        let f_center = self.eval(center, int)?;
+        let mut kronrod = f_center * KRONROD_WEIGHTS[7];
+        let mut gauss = f_center * GAUSS_WEIGHTS[3];
+        for (i, (node, weight)) in KRONROD_NODES[..7]
+            .iter()
+            .zip(&KRONROD_WEIGHTS[..7])
+            .enumerate()
+        {
+            let dx = half_width * node;
+            let sum = self.eval(center - dx, int)? + self.eval(center + dx, int)?;
+            kronrod += weight * sum;
+            if i % 2 == 1 {
+                gauss += GAUSS_WEIGHTS[i / 2] * sum;
+            }
+        }
+        Ok((kronrod * half_width, ((kronrod - gauss) * half_width).abs()))
+    }
+}
+
+/// Numerically integrates the function `f` from `a` to `b`, using adaptive
+/// Gauss-Kronrod quadrature. The result has the unit of `f` multiplied by
+/// the unit of its argument.
+pub(crate) fn integrate<I: Interrupt>(
+    f: &Value,
+    a: Number,
+    b: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let x_unit = a.clone().unit_one();
+    let b = b.convert_to(x_unit.clone(), int)?;
+    let lower = a.clone().try_as_f64(int)?;
+    let upper = b.clone().try_as_f64(int)?;
+    // the endpoints may be singular, so use the midpoint to find out
+    // which unit the function returns
+    let mid = a.add(b, int)?.div(2.into(), int)?;
+    let y_unit = call(f, mid, context, int)?.unit_one();
+    let mut integrand = Integrand {
+        f,
+        x_unit: x_unit.clone(),
+        y_unit: y_unit.clone(),
+        context,
+    };
+
+    let (value, error) = integrand.gauss_kronrod(lower, upper, int)?;
+    let mut intervals = vec![(lower, upper, value, error)];
+    loop {
+        let total: f64 = intervals.iter().map(|i| i.2).sum();
+        let total_error: f64 = intervals.iter().map(|i| i.3).sum();
+        if total_error <= 1e-10 * total.abs() || total_error == 0.0 {
+            let unit = y_unit.mul(x_unit, int)?;
+            return approximate_result(total, unit, "integral", int);
+        }
+        if intervals.len() >= MAX_INTERVALS {
+            return Err(FendError::DoesNotConverge("integral"));
+        }
+        // split the interval with the largest error estimate in half
+        let mut worst = 0;
+        for (i, interval) in intervals.iter().enumerate() {
+            if interval.3 > intervals[worst].3 {
+                worst = i;
+            }
+        }
+        let (a, b, _, _) = intervals.swap_remove(worst);
+        let mid = a + (b - a) / 2.0;
+        if (mid - a) * (b - mid) <= 0.0 {
+            return Err(FendError::DoesNotConverge("integral"));
+        }
+        let (value, error) = integrand.gauss_kronrod(a, mid, int)?;
+        intervals.push((a, mid, value, error));
+        let (value, error) = integrand.gauss_kronrod(mid, b, int)?;
+        intervals.push((mid, b, value, error));
+    }
+}
+
+/// Extrapolates the one-sided differences `(f(x + h) - f(x)) / h` with step
+/// sizes `h` and `h/2`, returning the estimate and its approximate error
+fn one_sided_difference<I: Interrupt>(
+    at_x: &Number,
+    half_step: Number,
+    full_step: Number,
+    h: &Number,
+    int: &I,
+) -> Result<(f64, f64), FendError> {
+    let coarse = full_step
+        .sub(at_x.clone(), int)?
+        .div(h.clone(), int)?
+        .try_as_f64(int)?;
+    let fine = half_step
+        .sub(at_x.clone(), int)?
+        .div(h.clone(), int)?
+        .mul(2.into(), int)?
+        .try_as_f64(int)?;
+    Ok((2.0 * fine - coarse, (fine - coarse).abs()))
+}
+
+/// Numerically differentiates the function `f` at `x`, using central
+/// differences with Richardson extrapolation
+pub(crate) fn derivative<I: Interrupt>(
+    f: &Value,
+    x: &Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let magnitude = x.clone().try_as_f64(int)?.abs();
+    let step = if magnitude > 0.0 { magnitude } else { 1.0 } * 1e-3;
+    let h = Number::from_f64(step, int)?.mul(x.clone().unit_one(), int)?;
+    let half = h.clone().div(2.into(), int)?;
+    let above = call(f, x.clone().add(h.clone(), int)?, context, int)?;
+    let below = call(f, x.clone().sub(h.clone(), int)?, context, int)?;
+    let half_above = call(f, x.clone().add(half.clone(), int)?, context, int)?;
+    let half_below = call(f, x.clone().sub(half, int)?, context, int)?;
+    // central differences `(f(x + h) - f(x - h)) / 2h` with step sizes h and h/2
+    let coarse = above
+        .clone()
+        .sub(below.clone(), int)?
+        .div(h.clone().mul(2.into(), int)?, int)?;
+    let fine = half_above
+        .clone()
+        .sub(half_below.clone(), int)?
+        .div(h.clone(), int)?;
+    let result = fine
+        .clone()
+        .mul(4.into(), int)?
+        .sub(coarse.clone(), int)?
+        .div(3.into(), int)?;
+
+    // if halving the step size changes the result significantly,
+    // the function isn't differentiable here (e.g. 1/x at 0)
+    let fine = fine.try_as_f64(int)?;
+    let coarse = coarse.try_as_f64(int)?;
+    if (fine - coarse).abs() > 1e-3 * fine.abs().max(coarse.abs()).max(1e-300) {
+        return Err(FendError::DoesNotConverge("derivative"));
+    }
+
+    // at a kink like `abs x` at 0 the central differences converge, but the
+    // derivatives from the left and from the right disagree by much more
+    // than their error
+    let at_x = call(f, x.clone(), context, int)?;
+    let (forward, forward_error) = one_sided_difference(&at_x, half_above, above, &h, int)?;
+    let (backward, backward_error) = one_sided_difference(&at_x, half_below, below, &-h, int)?;
+    let tolerance =
+        10.0 * forward_error.max(backward_error) + 1e-3 * forward.abs().max(backward.abs());
+    if (forward - backward).abs() > tolerance {
+        return Err(FendError::NotDifferentiable);
+    }
+    let unit = result.clone().unit_one();
+    let value = result.try_as_f64(int)?;
+    approximate_result(value, unit, "derivative", int)
+}
+
+/// Calls `f` with every integer from `start` to `end` (inclusive), and
+/// combines the results using exact arithmetic. Returns `None` if the
+/// range is empty.
+fn fold_range<I: Interrupt>(
+    f: &Value,
+    start: Number,
+    end: Number,
+    mut combine: impl FnMut(Number, Number) -> Result<Number, FendError>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<Number>, FendError> {
+    // only integers are allowed as bounds
+    start.clone().abs(int)?.try_as_usize(int)?;
+    let len = match end
+        .sub(start.clone(), int)?
+        .add(1.into(), int)?
+        .try_as_usize(int)
+    {
+        Ok(len) => len,
+        Err(FendError::NegativeNumbersNotAllowed) => 0,
+        Err(e) => return Err(e),
+    };
+    let mut result = None;
+    let mut k = start;
+    for _ in 0..len {
+        test_int(int)?;
+        let term = call(f, k.clone(), context, int)?;
+        result = Some(match result {
+            Some(result) => combine(result, term)?,
+            None => term,
+        });
+        k = k.add(1.into(), int)?;
+    }
+    Ok(result)
+}
+
+/// Computes `f(start) + f(start + 1) + ... + f(end)`
+pub(crate) fn sum<I: Interrupt>(
+    f: &Value,
+    start: Number,
+    end: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let result = fold_range(f, start, end, |a, b| a.add(b, int), context, int)?;
+    Ok(result.unwrap_or_else(|| 0.into()))
+}
+
+/// Computes `f(start) * f(start + 1) * ... * f(end)`
+pub(crate) fn product<I: Interrupt>(
+    f: &Value,
+    start: Number,
+    end: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let result = fold_range(f, start, end, |a, b| a.mul(b, int), context, int)?;
+    Ok(result.unwrap_or_else(|| 1.into()))
+}
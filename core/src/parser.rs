@@ -1,5 +1,8 @@
 use crate::ast::{Bop, Expr};
+use crate::ident::Ident;
 use crate::lexer::{Symbol, Token};
+use crate::num::Number;
+use crate::units::PrefixRule;
 use crate::value::Value;
 use std::fmt;
 
@@ -18,6 +21,7 @@ pub(crate) enum ParseError {
     ExpectedIdentifierInAssignment,
     ExpectedDotInLambda(Box<ParseError>),
     InvalidMixedFraction,
+    UnknownPrefixRule(Ident),
 }
 
 impl fmt::Display for ParseError {
@@ -44,6 +48,10 @@ impl fmt::Display for ParseError {
                 write!(f, "missing '.' in lambda (expected e.g. \\x.x)")
             }
             Self::InvalidMixedFraction => write!(f, "invalid mixed fraction"),
+            Self::UnknownPrefixRule(marker) => write!(
+                f,
+                "unknown prefix rule '{marker}@', expected one of l@, lp@, s@ or sp@"
+            ),
         }
     }
 }
@@ -83,10 +91,54 @@ fn parse_number(input: &[Token]) -> ParseResult<'_> {
     }
 }
 
+// matches one of the given identifiers, e.g. `off` in `10% off 60`
+fn parse_fixed_ident<'a>(input: &'a [Token], idents: &[&str]) -> ParseResult<'a, ()> {
+    match parse_token(input)? {
+        (Token::Ident(ident), remaining) if idents.contains(&ident.as_str()) => Ok(((), remaining)),
+        _ => Err(ParseError::ExpectedIdentifier),
+    }
+}
+
+const PERCENT_IDENTS: &[&str] = &["%", "percent"];
+
+fn is_percent_ident(expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(ident) if PERCENT_IDENTS.contains(&ident.as_str()))
+}
+
+/// returns true if the expression is syntactically a percentage of some
+/// operand, e.g. `15%` or `(a + 5) percent`
+fn is_percentage(expr: &Expr) -> bool {
+    match expr {
+        Expr::Apply(_, x) | Expr::ApplyMul(_, x) => is_percent_ident(x),
+        Expr::Parens(x) | Expr::UnaryMinus(x) | Expr::UnaryPlus(x) => is_percentage(x),
+        _ => false,
+    }
+}
+
+/// returns true if the expression results in a percentage, e.g. `5% * 2`
+/// or `5% + 10%`, but not `1 + 5%`
+fn is_percentage_valued(expr: &Expr) -> bool {
+    match expr {
+        Expr::Bop(Bop::Plus | Bop::Minus, a, b) => {
+            is_percentage_valued(a) && is_percentage_valued(b)
+        }
+        Expr::Bop(Bop::Mul, a, b) => is_percentage_valued(a) || is_percentage_valued(b),
+        Expr::Bop(Bop::Div, x, _) | Expr::Parens(x) | Expr::UnaryMinus(x) | Expr::UnaryPlus(x) => {
+            is_percentage_valued(x)
+        }
+        _ => is_percentage(expr),
+    }
+}
+
 fn parse_ident(input: &[Token]) -> ParseResult<'_> {
     match parse_token(input)? {
         (Token::Ident(ident), remaining) => {
             if let Ok(((), remaining2)) = parse_fixed_symbol(remaining, Symbol::Of) {
+                if PERCENT_IDENTS.contains(&ident.as_str()) {
+                    // percentages with an operand are handled by `parse_percent_of`,
+                    // so this is e.g. `% of 200`
+                    return Err(ParseError::ExpectedANumber);
+                }
                 let (inner, remaining3) = parse_parens_or_literal(remaining2)?;
                 Ok((Expr::Of(ident, Box::new(inner)), remaining3))
             } else {
@@ -102,13 +154,65 @@ fn parse_parens(input: &[Token]) -> ParseResult<'_> {
     if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::CloseParens) {
         return Ok((Expr::Literal(Value::Unit), remaining));
     }
-    let (inner, mut input) = parse_expression(input)?;
+    let (inner, input) = parse_expression(input)?;
+    let input = parse_close_parens(input)?;
+    Ok((Expr::Parens(Box::new(inner)), input))
+}
+
+fn parse_close_parens(input: &[Token]) -> Result<&[Token], ParseError> {
     // allow omitting closing parentheses at end of input
-    if !input.is_empty() {
-        let (_, remaining) = parse_fixed_symbol(input, Symbol::CloseParens)?;
-        input = remaining;
+    if input.is_empty() {
+        return Ok(input);
+    }
+    let ((), remaining) = parse_fixed_symbol(input, Symbol::CloseParens)?;
+    Ok(remaining)
+}
+
+/// Parses the argument of a function call, which may be a comma-separated
+/// list of arguments like in `integrate(f, 0, 1)`. A single argument is
+/// parsed like any other parenthesised expression.
+fn parse_call_arguments(input: &[Token]) -> Result<(Vec<Expr>, &[Token]), ParseError> {
+    let ((), input) = parse_fixed_symbol(input, Symbol::OpenParens)?;
+    let (first, remaining) = parse_function(input)?;
+    let Ok(((), mut remaining)) = parse_fixed_symbol(remaining, Symbol::Comma) else {
+        let (inner, remaining) = if let Ok(remaining) = parse_close_parens(remaining) {
+            (first, remaining)
+        } else {
+            let (inner, remaining) = parse_expression(input)?;
+            (inner, parse_close_parens(remaining)?)
+        };
+        let (result, remaining) = parse_factorial_cont(Expr::Parens(Box::new(inner)), remaining);
+        let (result, remaining) = parse_power_cont(result, remaining)?;
+        return Ok((vec![result], remaining));
+    };
+    let mut args = vec![first];
+    loop {
+        let (arg, input) = parse_function(remaining)?;
+        args.push(arg);
+        if let Ok(((), input)) = parse_fixed_symbol(input, Symbol::Comma) {
+            remaining = input;
+        } else {
+            return Ok((args, parse_close_parens(input)?));
+        }
+    }
+}
+
+fn parse_list(input: &[Token]) -> ParseResult<'_> {
+    let ((), mut input) = parse_fixed_symbol(input, Symbol::OpenBracket)?;
+    let mut items = vec![];
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::CloseBracket) {
+        return Ok((Expr::List(items), remaining));
+    }
+    loop {
+        let (item, remaining) = parse_function(input)?;
+        items.push(item);
+        if let Ok(((), remaining)) = parse_fixed_symbol(remaining, Symbol::Comma) {
+            input = remaining;
+        } else {
+            let ((), remaining) = parse_fixed_symbol(remaining, Symbol::CloseBracket)?;
+            return Ok((Expr::List(items), remaining));
+        }
     }
-    Ok((Expr::Parens(Box::new(inner)), input))
 }
 
 fn parse_backslash_lambda(input: &[Token]) -> ParseResult<'_> {
@@ -132,18 +236,23 @@ fn parse_parens_or_literal(input: &[Token]) -> ParseResult<'_> {
         Token::Ident(_) => parse_ident(input),
         Token::StringLiteral(s) => Ok((Expr::Literal(Value::String(s)), remaining)),
         Token::Symbol(Symbol::OpenParens) => parse_parens(input),
+        Token::Symbol(Symbol::OpenBracket) => parse_list(input),
         Token::Symbol(Symbol::Backslash) => parse_backslash_lambda(input),
         Token::Symbol(s) => Err(ParseError::UnexpectedSymbol(s)),
     }
 }
 
 fn parse_factorial(input: &[Token]) -> ParseResult<'_> {
-    let (mut res, mut input) = parse_parens_or_literal(input)?;
+    let (res, input) = parse_parens_or_literal(input)?;
+    Ok(parse_factorial_cont(res, input))
+}
+
+fn parse_factorial_cont(mut res: Expr, mut input: &[Token]) -> (Expr, &[Token]) {
     while let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Factorial) {
         res = Expr::Factorial(Box::new(res));
         input = remaining;
     }
-    Ok((res, input))
+    (res, input)
 }
 
 fn parse_power(input: &[Token], allow_unary: bool) -> ParseResult<'_> {
@@ -163,7 +272,11 @@ fn parse_power(input: &[Token], allow_unary: bool) -> ParseResult<'_> {
             return Ok((Expr::UnaryDiv(Box::new(result)), remaining));
         }
     }
-    let (mut result, mut input) = parse_factorial(input)?;
+    let (result, input) = parse_factorial(input)?;
+    parse_power_cont(result, input)
+}
+
+fn parse_power_cont(mut result: Expr, mut input: &[Token]) -> ParseResult<'_> {
     if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Pow) {
         let (rhs, remaining) = parse_power(remaining, true)?;
         result = Expr::Bop(Bop::Pow, Box::new(result), Box::new(rhs));
@@ -172,8 +285,50 @@ fn parse_power(input: &[Token], allow_unary: bool) -> ParseResult<'_> {
     Ok((result, input))
 }
 
+// `15% of 200` is parsed as `15 (200/100)`
+fn parse_percent_of(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_ident(input, PERCENT_IDENTS)?;
+    let ((), input) = parse_fixed_symbol(input, Symbol::Of)?;
+    let (inner, input) = parse_parens_or_literal(input)?;
+    let hundred = Expr::Literal(Value::Num(Box::new(Number::from(100))));
+    Ok((
+        Expr::Parens(Box::new(Expr::Bop(
+            Bop::Div,
+            Box::new(inner),
+            Box::new(hundred),
+        ))),
+        input,
+    ))
+}
+
 fn parse_apply_cont<'a>(input: &'a [Token], lhs: &Expr) -> ParseResult<'a> {
-    let (rhs, input) = parse_power(input, false)?;
+    // keywords of percentage expressions, e.g. `80 is what % of 200`,
+    // or a pixel density like `at 96 dpi`
+    if (is_percentage(lhs) && parse_fixed_ident(input, &["off"]).is_ok())
+        || parse_pixel_density_cont(input).is_ok()
+        || parse_fixed_ident(input, &["is"])
+            .and_then(|((), input)| parse_fixed_ident(input, &["what"]))
+            .is_ok()
+    {
+        return Err(ParseError::InvalidApplyOperands);
+    }
+    let (rhs, input) = if let Ok(res) = parse_percent_of(input) {
+        res
+    } else if parse_fixed_symbol(input, Symbol::OpenParens).is_ok()
+        && parse_fixed_symbol(&input[1..], Symbol::CloseParens).is_err()
+    {
+        let (mut args, input) = parse_call_arguments(input)?;
+        if args.len() > 1 {
+            // e.g. `integrate(f, 0, 1)`, which is the same as `integrate f 0 1`
+            let call = args.into_iter().fold(lhs.clone(), |f, arg| {
+                Expr::ApplyFunctionCall(Box::new(f), Box::new(arg))
+            });
+            return Ok((call, input));
+        }
+        (args.remove(0), input)
+    } else {
+        parse_power(input, false)?
+    };
     Ok((
         match (lhs, &rhs) {
             (
@@ -277,6 +432,14 @@ fn parse_modulo_cont(input: &[Token]) -> ParseResult<'_> {
     Ok((b, input))
 }
 
+// e.g. `(mod 13)` in `5 (mod 13)`
+fn parse_modular_cont(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_symbol(input, Symbol::OpenParens)?;
+    let ((), input) = parse_fixed_symbol(input, Symbol::Mod)?;
+    let (modulus, input) = parse_expression(input)?;
+    Ok((modulus, parse_close_parens(input)?))
+}
+
 fn parse_multiplicative(input: &[Token]) -> ParseResult<'_> {
     let (mut res, mut input) = parse_power(input, true)?;
     loop {
@@ -289,10 +452,13 @@ fn parse_multiplicative(input: &[Token]) -> ParseResult<'_> {
         } else if let Ok((term, remaining)) = parse_modulo_cont(input) {
             res = Expr::Bop(Bop::Mod, Box::new(res.clone()), Box::new(term));
             input = remaining;
+        } else if let Ok((modulus, remaining)) = parse_modular_cont(input) {
+            res = Expr::Modular(Box::new(res.clone()), Box::new(modulus));
+            input = remaining;
         } else if let Ok((new_res, remaining)) = parse_mixed_fraction(input, &res) {
             res = new_res;
             input = remaining;
-        } else if let Ok((new_res, remaining)) = parse_apply_cont(input, &res) {
+        } else if let Ok((new_res, remaining)) = parse_apply_to_product(input, &res) {
             res = new_res;
             input = remaining;
         } else {
@@ -302,8 +468,31 @@ fn parse_multiplicative(input: &[Token]) -> ParseResult<'_> {
     Ok((res, input))
 }
 
-fn parse_implicit_addition(input: &[Token]) -> ParseResult<'_> {
+// applies e.g. a unit to the last factor of a product, so that `2 * 30 dBm`
+// is parsed as `2 * (30 dBm)`
+fn parse_apply_to_product<'a>(input: &'a [Token], lhs: &Expr) -> ParseResult<'a> {
+    if let Expr::Bop(Bop::Mul, a, b) = lhs {
+        let (b, remaining) = parse_apply_to_product(input, b)?;
+        return Ok((Expr::Bop(Bop::Mul, a.clone(), Box::new(b)), remaining));
+    }
+    parse_apply_cont(input, lhs)
+}
+
+// e.g. `5 ∠ 30°`
+fn parse_polar(input: &[Token]) -> ParseResult<'_> {
     let (res, input) = parse_multiplicative(input)?;
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::Angle) {
+        let (angle, remaining) = parse_multiplicative(remaining)?;
+        return Ok((
+            Expr::Bop(Bop::Polar, Box::new(res), Box::new(angle)),
+            remaining,
+        ));
+    }
+    Ok((res, input))
+}
+
+fn parse_implicit_addition(input: &[Token]) -> ParseResult<'_> {
+    let (res, input) = parse_polar(input)?;
     if let Ok((rhs, remaining)) = parse_implicit_addition(input) {
         // n i n i, n i i n i i, etc. (n: number literal, i: identifier)
         if let (
@@ -334,22 +523,61 @@ fn parse_subtraction_cont(input: &[Token]) -> ParseResult<'_> {
 
 fn parse_to_cont(input: &[Token]) -> ParseResult<'_> {
     let (_, input) = parse_fixed_symbol(input, Symbol::UnitConversion)?;
-    let (b, input) = parse_implicit_addition(input)?;
-    Ok((b, input))
+    let (b, mut input) = parse_implicit_addition(input)?;
+    let mut units = vec![b];
+    while let Ok((unit, remaining)) = parse_compound_unit_cont(input) {
+        units.push(unit);
+        input = remaining;
+    }
+    if units.len() == 1 {
+        return Ok((units.remove(0), input));
+    }
+    Ok((Expr::List(units), input))
+}
+
+// e.g. `at 96 dpi` or `at 160`. `at` is only a pixel density if it is
+// followed by a number, otherwise it is the technical atmosphere.
+fn parse_pixel_density_cont(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_ident(input, &["at"])?;
+    if !matches!(input.first(), Some(Token::Num(_))) {
+        return Err(ParseError::ExpectedANumber);
+    }
+    parse_implicit_addition(input)
+}
+
+// further target unit when splitting a value across several units,
+// e.g. `, minutes` or `and in` in `5.75 ft to ft and in`
+fn parse_compound_unit_cont(input: &[Token]) -> ParseResult<'_> {
+    let input = if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::Comma) {
+        parse_fixed_symbol(remaining, Symbol::BitwiseAnd).map_or(remaining, |((), r)| r)
+    } else {
+        parse_fixed_symbol(input, Symbol::BitwiseAnd)?.1
+    };
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::UnitConversion) {
+        // `in` is lexed as a conversion keyword, but here it can only mean inches
+        return Ok((Expr::Ident(Ident::new_str("inch")), remaining));
+    }
+    if !matches!(input.first(), Some(Token::Ident(_))) {
+        return Err(ParseError::ExpectedIdentifier);
+    }
+    parse_implicit_addition(input)
 }
 
 fn parse_additive(input: &[Token]) -> ParseResult<'_> {
     let (mut res, mut input) = parse_implicit_addition(input)?;
     loop {
         if let Ok((term, remaining)) = parse_addition_cont(input) {
-            res = Expr::Bop(Bop::Plus, Box::new(res), Box::new(term));
+            res = percentage_change(Bop::Plus, res, term);
             input = remaining;
         } else if let Ok((term, remaining)) = parse_subtraction_cont(input) {
-            res = Expr::Bop(Bop::Minus, Box::new(res), Box::new(term));
+            res = percentage_change(Bop::Minus, res, term);
             input = remaining;
         } else if let Ok((term, remaining)) = parse_to_cont(input) {
             res = Expr::As(Box::new(res), Box::new(term));
             input = remaining;
+        } else if let Ok((density, remaining)) = parse_pixel_density_cont(input) {
+            res = Expr::AtPixelDensity(Box::new(res), Box::new(density));
+            input = remaining;
         } else {
             break;
         }
@@ -357,11 +585,83 @@ fn parse_additive(input: &[Token]) -> ParseResult<'_> {
     Ok((res, input))
 }
 
+// Adding or subtracting a percentage, e.g. `200 + 15%`, increases or
+// decreases the value by that percentage, i.e. `200 * (1 + 15%)`.
+// Percentages can still be added together as usual, e.g. `5% * 2 + 10%`.
+fn percentage_change(bop: Bop, lhs: Expr, rhs: Expr) -> Expr {
+    if !is_percentage(&rhs) || is_percentage_valued(&lhs) {
+        return Expr::Bop(bop, Box::new(lhs), Box::new(rhs));
+    }
+    let one = Expr::Literal(Value::Num(Box::new(Number::from(1))));
+    Expr::Bop(
+        Bop::Mul,
+        Box::new(lhs),
+        Box::new(Expr::Parens(Box::new(Expr::Bop(
+            bop,
+            Box::new(one),
+            Box::new(rhs),
+        )))),
+    )
+}
+
+// e.g. `percent change from 80 to 100`
+fn parse_percent_change(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_ident(input, &["%", "percent", "percentage"])?;
+    let ((), input) = parse_fixed_ident(input, &["change"])?;
+    let ((), input) = parse_fixed_ident(input, &["from"])?;
+    let (from, input) = parse_implicit_addition(input)?;
+    let ((), input) = parse_fixed_symbol(input, Symbol::UnitConversion)?;
+    let (to, input) = parse_implicit_addition(input)?;
+    // (to - from) / from, as a percentage
+    let difference = Expr::Parens(Box::new(Expr::Bop(
+        Bop::Minus,
+        Box::new(to),
+        Box::new(from.clone()),
+    )));
+    Ok((
+        Expr::As(
+            Box::new(Expr::Bop(Bop::Div, Box::new(difference), Box::new(from))),
+            Box::new(Expr::Ident(Ident::new_str("%"))),
+        ),
+        input,
+    ))
+}
+
+// Percentage expressions: `percent change from 80 to 100`,
+// `80 is what % of 200` and `10% off 59.99 USD`
+fn parse_percentages(input: &[Token]) -> ParseResult<'_> {
+    if let Ok(res) = parse_percent_change(input) {
+        return Ok(res);
+    }
+    let (lhs, input) = parse_additive(input)?;
+    if let Ok(((), remaining)) = parse_fixed_ident(input, &["is"])
+        .and_then(|((), input)| parse_fixed_ident(input, &["what"]))
+        .and_then(|((), input)| parse_fixed_ident(input, PERCENT_IDENTS))
+        .and_then(|((), input)| parse_fixed_symbol(input, Symbol::Of))
+    {
+        let (rhs, remaining) = parse_additive(remaining)?;
+        return Ok((
+            Expr::As(
+                Box::new(Expr::Bop(Bop::Div, Box::new(lhs), Box::new(rhs))),
+                Box::new(Expr::Ident(Ident::new_str("%"))),
+            ),
+            remaining,
+        ));
+    }
+    if is_percentage(&lhs) {
+        if let Ok(((), remaining)) = parse_fixed_ident(input, &["off"]) {
+            let (rhs, remaining) = parse_additive(remaining)?;
+            return Ok((percentage_change(Bop::Minus, rhs, lhs), remaining));
+        }
+    }
+    Ok((lhs, input))
+}
+
 fn parse_bitshifts(input: &[Token]) -> ParseResult<'_> {
-    let (mut result, mut input) = parse_additive(input)?;
+    let (mut result, mut input) = parse_percentages(input)?;
     loop {
         if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::ShiftLeft) {
-            let (rhs, remaining) = parse_additive(remaining)?;
+            let (rhs, remaining) = parse_percentages(remaining)?;
             result = Expr::Bop(
                 Bop::Bitwise(crate::ast::BitwiseBop::LeftShift),
                 Box::new(result),
@@ -369,7 +669,7 @@ fn parse_bitshifts(input: &[Token]) -> ParseResult<'_> {
             );
             input = remaining;
         } else if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::ShiftRight) {
-            let (rhs, remaining) = parse_additive(remaining)?;
+            let (rhs, remaining) = parse_percentages(remaining)?;
             result = Expr::Bop(
                 Bop::Bitwise(crate::ast::BitwiseBop::RightShift),
                 Box::new(result),
@@ -437,7 +737,66 @@ fn parse_function(input: &[Token]) -> ParseResult<'_> {
     Ok((lhs, input))
 }
 
+// e.g. `solve x: x^2 + 3x = 10`
+fn parse_solve(input: &[Token]) -> ParseResult<'_> {
+    let input = match parse_token(input)? {
+        (Token::Ident(ident), remaining) if ident.as_str() == "solve" => remaining,
+        _ => return Err(ParseError::ExpectedIdentifier),
+    };
+    let (Token::Ident(var), input) = parse_token(input)? else {
+        return Err(ParseError::ExpectedIdentifier);
+    };
+    let ((), input) = parse_fixed_symbol(input, Symbol::Fn)?;
+    let (lhs, input) = parse_function(input)?;
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::Equals) {
+        let (rhs, remaining) = parse_function(remaining)?;
+        return Ok((
+            Expr::Solve(var, Box::new(lhs), Some(Box::new(rhs))),
+            remaining,
+        ));
+    }
+    Ok((Expr::Solve(var, Box::new(lhs), None), input))
+}
+
+// e.g. `unit smoot = 67 in`, `unit l@smoot = 67 in` or `unit widget`
+fn parse_unit_definition(input: &[Token]) -> ParseResult<'_> {
+    let (Token::Ident(mut name), mut input) = parse_token(input)? else {
+        return Err(ParseError::ExpectedIdentifier);
+    };
+    let mut prefix_rule = PrefixRule::NoPrefixesAllowed;
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::At) {
+        prefix_rule = PrefixRule::from_marker(name.as_str())
+            .ok_or_else(|| ParseError::UnknownPrefixRule(name.clone()))?;
+        let (Token::Ident(unit_name), remaining) = parse_token(remaining)? else {
+            return Err(ParseError::ExpectedIdentifier);
+        };
+        (name, input) = (unit_name, remaining);
+    }
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::Equals) {
+        let (definition, remaining) = parse_function(remaining)?;
+        return Ok((
+            Expr::UnitDefinition(name, prefix_rule, Some(Box::new(definition))),
+            remaining,
+        ));
+    }
+    Ok((Expr::UnitDefinition(name, prefix_rule, None), input))
+}
+
 fn parse_assignment(input: &[Token]) -> ParseResult<'_> {
+    if let Ok(res) = parse_solve(input) {
+        return Ok(res);
+    }
+    if let [Token::Ident(keyword), remaining @ ..] = input {
+        if keyword.as_str() == "unit" && matches!(remaining.first(), Some(Token::Ident(_))) {
+            return parse_unit_definition(remaining);
+        }
+        // e.g. `describe kelvin` or `help kelvin`
+        if let ("describe" | "help", [Token::Ident(name), remaining @ ..]) =
+            (keyword.as_str(), remaining)
+        {
+            return Ok((Expr::Describe(name.clone()), remaining));
+        }
+    }
     let (lhs, input) = parse_function(input)?;
     if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Equals) {
         if let Expr::Ident(s) = lhs {
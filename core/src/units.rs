@@ -1,13 +1,17 @@
 use std::borrow::Cow;
+use std::io;
 
 use crate::error::{FendError, Interrupt};
 use crate::eval::evaluate_to_value;
 use crate::num::Number;
+use crate::serialize::{deserialize_string, deserialize_u8, serialize_string, serialize_u8};
 use crate::value::Value;
 
 mod builtin;
 
-pub(crate) use builtin::IMPLICIT_UNIT_MAP;
+pub(crate) use builtin::{
+    query_currency_format, query_substance_density, CurrencyFormat, IMPLICIT_UNIT_MAP,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) enum PrefixRule {
@@ -18,14 +22,208 @@ pub(crate) enum PrefixRule {
     ShortPrefix,
 }
 
+impl PrefixRule {
+    /// Parses a marker like `l` in `unit l@smoot = 67 in`
+    pub(crate) fn from_marker(marker: &str) -> Option<Self> {
+        Some(match marker {
+            "l" => Self::LongPrefixAllowed,
+            "lp" => Self::LongPrefix,
+            "s" => Self::ShortPrefixAllowed,
+            "sp" => Self::ShortPrefix,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn marker(self) -> &'static str {
+        match self {
+            Self::NoPrefixesAllowed => "",
+            Self::LongPrefixAllowed => "l@",
+            Self::LongPrefix => "lp@",
+            Self::ShortPrefixAllowed => "s@",
+            Self::ShortPrefix => "sp@",
+        }
+    }
+
+    pub(crate) fn serialize(self, write: &mut impl io::Write) -> Result<(), FendError> {
+        serialize_u8(
+            match self {
+                Self::NoPrefixesAllowed => 0,
+                Self::LongPrefixAllowed => 1,
+                Self::LongPrefix => 2,
+                Self::ShortPrefixAllowed => 3,
+                Self::ShortPrefix => 4,
+            },
+            write,
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn deserialize(read: &mut impl io::Read) -> Result<Self, FendError> {
+        Ok(match deserialize_u8(read)? {
+            0 => Self::NoPrefixesAllowed,
+            1 => Self::LongPrefixAllowed,
+            2 => Self::LongPrefix,
+            3 => Self::ShortPrefixAllowed,
+            4 => Self::ShortPrefix,
+            _ => return Err(FendError::DeserializationError),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct UnitDef {
-    singular: &'static str,
-    plural: &'static str,
+    singular: Cow<'static, str>,
+    plural: Cow<'static, str>,
+    prefix_rule: PrefixRule,
+    value: Value,
+}
+
+/// A unit defined at runtime with e.g. `unit smoot = 67 in`
+#[derive(Clone, Debug)]
+pub(crate) struct CustomUnit {
+    name: String,
     prefix_rule: PrefixRule,
     value: Value,
 }
 
+impl CustomUnit {
+    pub(crate) fn serialize(&self, write: &mut impl io::Write) -> Result<(), FendError> {
+        serialize_string(self.name.as_str(), write)?;
+        self.prefix_rule.serialize(write)?;
+        self.value.serialize(write)?;
+        Ok(())
+    }
+
+    pub(crate) fn deserialize(read: &mut impl io::Read) -> Result<Self, FendError> {
+        Ok(Self {
+            name: deserialize_string(read)?,
+            prefix_rule: PrefixRule::deserialize(read)?,
+            value: Value::deserialize(read)?,
+        })
+    }
+
+    fn matches(&self, ident: &str, short_prefixes: bool, case_sensitive: bool) -> bool {
+        if self.prefix_rule == PrefixRule::ShortPrefix && !short_prefixes {
+            return false;
+        }
+        if case_sensitive {
+            self.name == ident
+        } else {
+            self.name.eq_ignore_ascii_case(ident)
+        }
+    }
+}
+
+/// Registers a new unit in the context, replacing any previous unit
+/// with the same name. Without a definition, a new base unit is created.
+/// Built-in units take precedence, so they cannot be redefined.
+pub(crate) fn define_custom_unit<I: Interrupt>(
+    name: &str,
+    prefix_rule: PrefixRule,
+    definition: Option<Value>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if builtin::query_unit(name, false, true)?.is_some() {
+        return Err(FendError::UnitAlreadyDefined(name.to_string()));
+    }
+    let name_cow: Cow<'static, str> = Cow::Owned(name.to_string());
+    let num = match definition {
+        None => Number::new_base_unit(name_cow.clone(), name_cow),
+        Some(definition) => {
+            let num = definition.expect_num()?;
+            if prefix_rule == PrefixRule::LongPrefix {
+                num
+            } else {
+                Number::create_unit_value_from_value(
+                    &num,
+                    Cow::Borrowed(""),
+                    name_cow.clone(),
+                    name_cow,
+                    int,
+                )?
+            }
+        }
+    };
+    let value = Value::Num(Box::new(num));
+    context.custom_units.retain(|unit| unit.name != name);
+    context.custom_units.push(CustomUnit {
+        name: name.to_string(),
+        prefix_rule,
+        value: value.clone(),
+    });
+    Ok(value)
+}
+
+/// Loads unit definitions in the format used by GNU units, e.g.
+/// `oilbarrel 42 gallons`, `myprefix- 1000` for a prefix or `widget !` for
+/// a new base unit. Directives (lines starting with `!`), function and
+/// table definitions, and units that are already built in are skipped.
+pub(crate) fn load_definitions<I: Interrupt>(
+    source: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    let strip_comment = |line: &str| line.split('#').next().unwrap_or_default().to_string();
+    let mut lines = source.lines().enumerate();
+    while let Some((idx, line)) = lines.next() {
+        let mut line = strip_comment(line);
+        // a trailing backslash continues the definition on the next line
+        while line.trim_end().ends_with('\\') {
+            line = line.trim_end().trim_end_matches('\\').to_string();
+            match lines.next() {
+                Some((_, next)) => line.push_str(&strip_comment(next)),
+                None => break,
+            }
+        }
+        load_definition(line.trim(), context, int)
+            .map_err(|e| FendError::UnitDefinitionError(idx + 1, Box::new(e)))?;
+    }
+    Ok(())
+}
+
+fn load_definition<I: Interrupt>(
+    line: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    if line.is_empty() || line.starts_with('!') {
+        return Ok(());
+    }
+    let (name, definition) = match line.split_once(char::is_whitespace) {
+        Some((name, definition)) => (name, definition.trim()),
+        None => return Err(FendError::UnsupportedUnitDefinition(line.to_string())),
+    };
+    // function and table definitions, e.g. `tempF(x) ...` or `zippers[in] ...`,
+    // aren't supported and are skipped so that the rest of the file loads
+    if name.contains(['(', '[']) || definition.starts_with(['[', '{']) {
+        return Ok(());
+    }
+    let (name, prefix_rule) = match name.strip_suffix('-') {
+        Some(prefix) => (prefix, PrefixRule::LongPrefix),
+        None => (name, PrefixRule::LongPrefixAllowed),
+    };
+    if builtin::query_unit(name, false, true)?.is_some() {
+        return Ok(());
+    }
+    let definition = if definition == "!" {
+        None
+    } else {
+        // `|` divides numbers, e.g. `1|3`
+        let definition = definition
+            .split_whitespace()
+            .map(|word| match word.split_once('|') {
+                Some((num, den)) => format!("({num}/{den})"),
+                None => word.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(evaluate_to_value(&definition, None, context, int)?)
+    };
+    define_custom_unit(name, prefix_rule, definition, context, int)?;
+    Ok(())
+}
+
 fn expr_unit<I: Interrupt>(
     singular: &'static str,
     plural: &'static str,
@@ -58,8 +256,8 @@ fn expr_unit<I: Interrupt>(
                 Cow::Borrowed(plural),
             ))),
             prefix_rule: rule,
-            singular,
-            plural,
+            singular: Cow::Borrowed(singular),
+            plural: Cow::Borrowed(plural),
         });
     }
     let (alias, definition) = definition
@@ -78,8 +276,8 @@ fn expr_unit<I: Interrupt>(
     Ok(UnitDef {
         value: Value::Num(Box::new(num)),
         prefix_rule: rule,
-        singular,
-        plural,
+        singular: Cow::Borrowed(singular),
+        plural: Cow::Borrowed(plural),
     })
 }
 
@@ -90,13 +288,8 @@ fn construct_prefixed_unit<I: Interrupt>(
 ) -> Result<Value, FendError> {
     let product = a.value.expect_num()?.mul(b.value.expect_num()?, int)?;
     assert_eq!(a.singular, a.plural);
-    let unit = Number::create_unit_value_from_value(
-        &product,
-        Cow::Borrowed(a.singular),
-        Cow::Borrowed(b.singular),
-        Cow::Borrowed(b.plural),
-        int,
-    )?;
+    let unit =
+        Number::create_unit_value_from_value(&product, a.singular, b.singular, b.plural, int)?;
     Ok(Value::Num(Box::new(unit)))
 }
 
@@ -128,7 +321,15 @@ pub(crate) fn query_unit_static<'a, I: Interrupt>(
             return Ok(value);
         }
     }
-    query_unit_case_sensitive(ident, false, context, int)
+    let value = query_unit_case_sensitive(ident, false, context, int)?;
+    if let Value::Num(num) = &value {
+        if let Some((prefix, name, _)) = num.leading_unit(int)? {
+            context.add_warning(format!(
+                "'{ident}' was interpreted as '{prefix}{name}', which has a different capitalization"
+            ));
+        }
+    }
+    Ok(value)
 }
 
 fn query_unit_case_sensitive<I: Interrupt>(
@@ -137,43 +338,124 @@ fn query_unit_case_sensitive<I: Interrupt>(
     context: &mut crate::Context,
     int: &I,
 ) -> Result<Value, FendError> {
-    match query_unit_internal(ident, false, case_sensitive, true, context, int) {
-        Err(FendError::IdentifierNotFound(_)) => (),
+    // an ambiguous unit name is only reported if it also can't be
+    // interpreted as a prefixed unit
+    let not_found = match query_unit_internal(ident, false, case_sensitive, true, context, int) {
+        Err(FendError::IdentifierNotFound(_)) => {
+            FendError::IdentifierNotFound(ident.to_string().into())
+        }
+        Err(e @ FendError::AmbiguousUnit(_, _)) => e,
         Err(e) => return Err(e),
         Ok(unit) => {
             // Return value without prefix. Note that lone short prefixes
             // won't be returned here.
             return Ok(unit.value);
         }
-    }
+    };
     let mut split_idx = ident.chars().next().unwrap().len_utf8();
     while split_idx < ident.len() {
         let (prefix, remaining_ident) = ident.split_at(split_idx);
         split_idx += remaining_ident.chars().next().unwrap().len_utf8();
-        let a = match query_unit_internal(prefix, true, case_sensitive, false, context, int) {
-            Err(FendError::IdentifierNotFound(_)) => continue,
-            Err(e) => {
-                return Err(e);
-            }
-            Ok(a) => a,
+        // a case-insensitive prefix like `m` could be `m` (milli) or `M` (mega)
+        let prefixes = if case_sensitive {
+            vec![prefix.to_string()]
+        } else {
+            prefix_capitalizations(prefix)
         };
+        let mut candidates = vec![];
+        for prefix in &prefixes {
+            match query_unit_internal(prefix, true, true, false, context, int) {
+                Err(FendError::IdentifierNotFound(_) | FendError::AmbiguousUnit(_, _)) => (),
+                Err(e) => return Err(e),
+                Ok(a) => candidates.push((prefix, a)),
+            }
+        }
+        if candidates.is_empty() {
+            continue;
+        }
         match query_unit_internal(remaining_ident, false, case_sensitive, false, context, int) {
-            Err(FendError::IdentifierNotFound(_)) => continue,
+            Err(FendError::IdentifierNotFound(_) | FendError::AmbiguousUnit(_, _)) => continue,
             Err(e) => return Err(e),
             Ok(b) => {
-                if (a.prefix_rule == PrefixRule::LongPrefix
-                    && b.prefix_rule == PrefixRule::LongPrefixAllowed)
-                    || (a.prefix_rule == PrefixRule::ShortPrefix
-                        && b.prefix_rule == PrefixRule::ShortPrefixAllowed)
-                {
-                    // now construct a new unit!
-                    return construct_prefixed_unit(a, b, int);
+                let mut matches: Vec<(&String, UnitDef, f64)> = vec![];
+                for (prefix, a) in candidates {
+                    if !((a.prefix_rule == PrefixRule::LongPrefix
+                        && b.prefix_rule == PrefixRule::LongPrefixAllowed)
+                        || (a.prefix_rule == PrefixRule::ShortPrefix
+                            && b.prefix_rule == PrefixRule::ShortPrefixAllowed))
+                    {
+                        continue;
+                    }
+                    let magnitude = a
+                        .value
+                        .clone()
+                        .expect_num()?
+                        .convert_to(Number::from(1), int)?
+                        .try_as_f64(int)?;
+                    if !matches
+                        .iter()
+                        .any(|(_, _, m)| (m - magnitude).abs() <= f64::EPSILON * m.abs())
+                    {
+                        matches.push((prefix, a, magnitude));
+                    }
+                }
+                match matches.len() {
+                    0 => return Err(not_found),
+                    1 => {
+                        // now construct a new unit!
+                        let (_, a, _) = matches.remove(0);
+                        return construct_prefixed_unit(a, b, int);
+                    }
+                    _ => {
+                        // only milli and mega are easily confused (e.g. in
+                        // `mhz`), so other prefixes keep the capitalization
+                        // they were typed in, e.g. `pv` is picovolts
+                        if !prefix.eq_ignore_ascii_case("m") {
+                            if let Some(idx) = matches.iter().position(|(p, _, _)| *p == prefix) {
+                                let (_, a, _) = matches.swap_remove(idx);
+                                return construct_prefixed_unit(a, b, int);
+                            }
+                        }
+                        return Err(FendError::AmbiguousUnit(
+                            ident.to_string(),
+                            matches
+                                .into_iter()
+                                .map(|(prefix, _, _)| {
+                                    let description = builtin::query_unit(prefix, true, true)
+                                        .ok()
+                                        .flatten()
+                                        .map_or("", |(_, _, def)| {
+                                            builtin::strip_prefix_rule(def).trim()
+                                        });
+                                    (format!("{prefix}{}", b.singular), description.to_string())
+                                })
+                                .collect(),
+                        ));
+                    }
                 }
-                return Err(FendError::IdentifierNotFound(ident.to_string().into()));
             }
         };
     }
-    Err(FendError::IdentifierNotFound(ident.to_string().into()))
+    Err(not_found)
+}
+
+/// Returns the capitalizations a case-insensitive prefix could have, e.g.
+/// `mi`, `MI` and `Mi` for `mI`
+fn prefix_capitalizations(prefix: &str) -> Vec<String> {
+    let mut chars = prefix.chars();
+    let capitalized = chars.next().map_or_else(String::new, |first| {
+        first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect()
+    });
+    let mut result = vec![];
+    for capitalization in [prefix.to_lowercase(), prefix.to_uppercase(), capitalized] {
+        if !result.contains(&capitalization) {
+            result.push(capitalization);
+        }
+    }
+    result
 }
 
 fn query_unit_internal<'a, I: Interrupt>(
@@ -191,7 +473,7 @@ fn query_unit_internal<'a, I: Interrupt>(
             return expr_unit("F", "F", "=\u{b0}F", context, int);
         }
     }
-    if let Some((s, p, expr)) = builtin::query_unit(ident, short_prefixes, case_sensitive) {
+    if let Some((s, p, expr)) = builtin::query_unit(ident, short_prefixes, case_sensitive)? {
         if expr == "$CURRENCY" {
             // we have a non-USD currency we need to convert
             let exchange_rate_fn = match context.get_exchange_rate {
@@ -214,19 +496,265 @@ fn query_unit_internal<'a, I: Interrupt>(
                 int,
             )?;
             Ok(UnitDef {
-                singular: s,
-                plural: p,
+                singular: Cow::Borrowed(s),
+                plural: Cow::Borrowed(p),
                 prefix_rule: PrefixRule::LongPrefixAllowed,
                 value: Value::Num(Box::new(value)),
             })
+        } else if expr == "$PIXEL" || expr == "$FONT_SIZE" {
+            // these depend on the pixel density and font size in the context
+            let value = if expr == "$PIXEL" {
+                let inch = evaluate_to_value("inch", None, context, int)?.expect_num()?;
+                inch.div(context.pixels_per_inch.clone(), int)?
+            } else {
+                let px = evaluate_to_value("px", None, context, int)?.expect_num()?;
+                context.base_font_size.clone().mul(px, int)?
+            };
+            let value = Number::create_unit_value_from_value(
+                &value,
+                Cow::Borrowed(""),
+                Cow::Borrowed(s),
+                Cow::Borrowed(p),
+                int,
+            )?;
+            Ok(UnitDef {
+                singular: Cow::Borrowed(s),
+                plural: Cow::Borrowed(p),
+                prefix_rule: PrefixRule::NoPrefixesAllowed,
+                value: Value::Num(Box::new(value)),
+            })
         } else {
             expr_unit(s, p, expr, context, int)
         }
+    } else if let Some(unit) = context
+        .custom_units
+        .iter()
+        .find(|unit| unit.matches(ident, short_prefixes, case_sensitive))
+    {
+        Ok(UnitDef {
+            singular: Cow::Owned(unit.name.clone()),
+            plural: Cow::Owned(unit.name.clone()),
+            prefix_rule: unit.prefix_rule,
+            value: unit.value.clone(),
+        })
     } else {
         Err(FendError::IdentifierNotFound(ident.to_string().into()))
     }
 }
 
+// prefixes used by `as auto_prefix`, as powers of ten or of two together
+// with their short and long forms
+const AUTO_DECIMAL_PREFIXES: &[(i32, &str, &str)] = &[
+    (24, "Y", "yotta"),
+    (21, "Z", "zetta"),
+    (18, "E", "exa"),
+    (15, "P", "peta"),
+    (12, "T", "tera"),
+    (9, "G", "giga"),
+    (6, "M", "mega"),
+    (3, "k", "kilo"),
+    (0, "", ""),
+    (-3, "m", "milli"),
+    (-6, "\u{b5}", "micro"),
+    (-9, "n", "nano"),
+    (-12, "p", "pico"),
+    (-15, "f", "femto"),
+    (-18, "a", "atto"),
+    (-21, "z", "zepto"),
+    (-24, "y", "yocto"),
+];
+
+const AUTO_BINARY_PREFIXES: &[(i32, &str, &str)] = &[
+    (80, "Yi", "yobi"),
+    (70, "Zi", "zebi"),
+    (60, "Ei", "exbi"),
+    (50, "Pi", "pebi"),
+    (40, "Ti", "tebi"),
+    (30, "Gi", "gibi"),
+    (20, "Mi", "mebi"),
+    (10, "Ki", "kibi"),
+    (0, "", ""),
+];
+
+/// Changes the prefix of the first unit so that the number is at least 1
+/// and less than 1000, e.g. `0.000000047 F` becomes `47 nF`. Binary
+/// prefixes are kept if the unit already has one. Units that don't allow
+/// prefixes, non-metric units like `foot` and units that aren't raised to
+/// a positive integer power are left unchanged.
+pub(crate) fn auto_prefix<I: Interrupt>(
+    num: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let Some((prefix, name, exponent)) = num.leading_unit(int)? else {
+        return Ok(num);
+    };
+    // the kilogram is the only base unit with a prefix in its name
+    let name = match name.as_str() {
+        "kilogram" => "gram",
+        name => name,
+    };
+    if builtin::query_currency_format(name).is_some() {
+        return Ok(num);
+    }
+    let unit = match query_unit_internal(name, false, true, false, context, int) {
+        Ok(unit) => unit,
+        Err(FendError::IdentifierNotFound(_)) => return Ok(num),
+        Err(e) => return Err(e),
+    };
+    let short = match unit.prefix_rule {
+        PrefixRule::ShortPrefixAllowed => true,
+        // avoid units like `kilofeet`: only metric units have a symbol
+        // that allows prefixes
+        PrefixRule::LongPrefixAllowed
+            if builtin::query_unit(name, false, true)?.is_none()
+                || builtin::has_prefixable_symbol(name) =>
+        {
+            false
+        }
+        _ => return Ok(num),
+    };
+    let unprefixed = num.convert_leading_unit_to(unit.value.clone().expect_num()?, int)?;
+    let magnitude = match unprefixed.clone().try_as_f64(int) {
+        Ok(value) if value != 0.0 && value.is_finite() => value.abs(),
+        Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+        _ => return Ok(unprefixed),
+    };
+    let binary = AUTO_BINARY_PREFIXES
+        .iter()
+        .any(|(_, s, l)| !s.is_empty() && (*s == prefix || *l == prefix));
+    #[allow(clippy::cast_precision_loss)]
+    let exponent = exponent as f64;
+    let (prefixes, step, power) = if binary {
+        (AUTO_BINARY_PREFIXES, 10.0, magnitude.log2() / exponent)
+    } else {
+        (AUTO_DECIMAL_PREFIXES, 3.0, magnitude.log10() / exponent)
+    };
+    // use the largest prefix that keeps the number at least 1, allowing
+    // for floating-point error in the logarithm. Values outside the range
+    // of prefixes keep the unprefixed unit instead of e.g. `1000000 Ym`.
+    let Some(&(_, short_prefix, long_prefix)) = prefixes
+        .iter()
+        .find(|(p, _, _)| f64::from(*p) <= power + 1e-9)
+        .filter(|(p, _, _)| power - f64::from(*p) < step - 1e-9)
+    else {
+        return Ok(unprefixed);
+    };
+    let prefix = if short { short_prefix } else { long_prefix };
+    if prefix.is_empty() {
+        return Ok(unprefixed);
+    }
+    let prefix = query_unit_internal(prefix, true, true, false, context, int)?;
+    let target = construct_prefixed_unit(prefix, unit, int)?.expect_num()?;
+    unprefixed.convert_leading_unit_to(target, int)
+}
+
+/// Converts a result with a compound unit like `mph h` into the preferred
+/// unit system, e.g. into `km` when using metric units. Results with a
+/// single unit, and dimensions without a preferred unit, are left unchanged.
+pub(crate) fn convert_to_unit_system<I: Interrupt>(
+    num: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let metric = match context.unit_system {
+        crate::UnitSystem::Default => return Ok(num),
+        _ if !num.has_compound_unit() => return Ok(num),
+        crate::UnitSystem::SiBase if !num.has_si_base_units(int)? => return Ok(num),
+        crate::UnitSystem::SiBase => {
+            let base_units = num.base_units(int)?;
+            let target = evaluate_to_value(&base_units, None, context, int)?.expect_num()?;
+            return num.convert_to(target, int);
+        }
+        crate::UnitSystem::Metric => true,
+        crate::UnitSystem::Imperial => false,
+    };
+    for (dimension, metric_units, imperial_units) in builtin::PREFERRED_UNITS {
+        if !num.has_base_units(dimension, int)? {
+            continue;
+        }
+        let units = if metric { metric_units } else { imperial_units };
+        for (idx, unit) in units.iter().enumerate() {
+            let target = evaluate_to_value(unit, None, context, int)?.expect_num()?;
+            let converted = num.clone().convert_to(target, int)?;
+            let at_least_one = match converted.clone().try_as_f64(int) {
+                Ok(value) => value.abs() >= 1.0,
+                Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+                Err(_) => true,
+            };
+            if at_least_one || idx == units.len() - 1 {
+                return Ok(converted);
+            }
+        }
+    }
+    Ok(num)
+}
+
+/// Returns the named SI derived units like newtons or watts, see
+/// `builtin::DERIVED_UNITS`
+pub(crate) fn derived_units<I: Interrupt>(
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    builtin::DERIVED_UNITS
+        .iter()
+        .map(|name| query_unit_static(name, context, int)?.expect_num())
+        .collect()
+}
+
+pub(crate) fn describe_unit<I: Interrupt>(
+    ident: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<crate::UnitDescription, FendError> {
+    let (name, description, definition, aliases) =
+        if let Some(doc) = builtin::query_unit_documentation(ident) {
+            let definition = match builtin::strip_prefix_rule(doc.definition).trim() {
+                "!" => "base unit",
+                "$CURRENCY" => "converted using exchange rates",
+                "$PIXEL" => "1/96 inch, or 1 / pixel density",
+                "$FONT_SIZE" => "16 px, or the base font size",
+                definition => definition,
+            };
+            (
+                doc.singular.to_string(),
+                doc.description.to_string(),
+                definition.to_string(),
+                doc.aliases.into_iter().map(ToString::to_string).collect(),
+            )
+        } else if let Some(unit) = context
+            .custom_units
+            .iter()
+            .find(|unit| unit.matches(ident, false, true))
+        {
+            (
+                unit.name.clone(),
+                "user-defined unit".to_string(),
+                String::new(),
+                vec![],
+            )
+        } else {
+            return Err(FendError::IdentifierNotFound(ident.to_string().into()));
+        };
+    let base_units = match query_unit(&name, context, int) {
+        Ok(Value::Num(num)) => num.base_units(int)?,
+        Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+        // e.g. currencies when no exchange rates are available
+        _ => String::new(),
+    };
+    let uncertainty = builtin::query_constant_uncertainty(&name)
+        .unwrap_or_default()
+        .to_string();
+    Ok(crate::UnitDescription {
+        name,
+        description,
+        definition,
+        base_units,
+        aliases,
+        uncertainty,
+    })
+}
+
 pub(crate) fn get_completions_for_prefix(prefix: &str) -> Vec<crate::Completion> {
     use crate::Completion;
 
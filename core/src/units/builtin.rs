@@ -1,3 +1,5 @@
+use crate::error::FendError;
+
 #[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
 struct UnitDef {
     singular: &'static str,
@@ -10,14 +12,29 @@ type UnitTuple = (&'static str, &'static str, &'static str, &'static str);
 
 const BASE_UNITS: &[UnitTuple] = &[
     ("unitless", "", "=1", ""),
-    ("second", "seconds", "l@!", ""),
-    ("meter", "meters", "l@!", ""),
-    ("kilogram", "kilograms", "l@!", ""),
-    ("kelvin", "", "l@!", ""),
-    ("ampere", "amperes", "l@!", ""),
-    ("mole", "moles", "l@!", ""),
-    ("candela", "candelas", "l@!", ""),
-    ("neper", "nepers", "l@!", ""),
+    ("second", "seconds", "l@!", "SI base unit of time"),
+    ("meter", "meters", "l@!", "SI base unit of length"),
+    ("kilogram", "kilograms", "l@!", "SI base unit of mass"),
+    ("kelvin", "", "l@!", "SI base unit of temperature"),
+    (
+        "ampere",
+        "amperes",
+        "l@!",
+        "SI base unit of electric current",
+    ),
+    (
+        "mole",
+        "moles",
+        "l@!",
+        "SI base unit of amount of substance",
+    ),
+    (
+        "candela",
+        "candelas",
+        "l@!",
+        "SI base unit of luminous intensity",
+    ),
+    ("neper", "nepers", "l@!", "logarithmic unit of ratio"),
 ];
 
 const BASE_UNIT_ABBREVIATIONS: &[UnitTuple] = &[
@@ -45,6 +62,11 @@ const TEMPERATURE_SCALES: &[UnitTuple] = &[
     ("fahrenheit", "", "l@!", ""),
     ("\u{b0}F", "", "fahrenheit", ""),
     ("oF", "", "=\u{b0}F", ""),
+    // temperature differences, which convert without an offset
+    ("delta_celsius", "", "1 kelvin", "temperature difference"),
+    ("\u{394}\u{b0}C", "", "delta_celsius", ""), // Greek capital delta
+    ("delta_fahrenheit", "", "5/9 K", "temperature difference"),
+    ("\u{394}\u{b0}F", "", "delta_fahrenheit", ""),
 ];
 
 const BITS_AND_BYTES: &[UnitTuple] = &[
@@ -231,10 +253,230 @@ const CONSTANTS: &[UnitTuple] = &[
         "=6.67430e-11 N m^2 / kg^2",
         "gravitational constant",
     ),
+    ("G_N", "", "=gravitational_constant", ""),
+    (
+        "reduced_planck",
+        "",
+        "=planck / (2 pi)",
+        "reduced Planck constant (exact)",
+    ),
+    ("hbar", "", "=reduced_planck", ""),
+    ("\u{127}", "", "=reduced_planck", ""),
+    ("elementary_charge", "", "=electron_charge", ""),
+    (
+        "molar_gas_constant",
+        "",
+        "=avogadro boltzmann",
+        "molar gas constant (exact)",
+    ),
+    (
+        "faraday_constant",
+        "",
+        "=avogadro electron_charge",
+        "Faraday constant (exact)",
+    ),
+    (
+        "stefan_boltzmann_constant",
+        "",
+        "=2 pi^5 boltzmann^4 / (15 planck^3 c^2) to W m^-2 K^-4",
+        "Stefan-Boltzmann constant (exact)",
+    ),
+    (
+        "wien_wavelength_displacement_constant",
+        "",
+        "=2.897771955e-3 m K",
+        "Wien wavelength displacement law constant",
+    ),
+    (
+        "josephson_constant",
+        "",
+        "=2 electron_charge / planck to Hz / V",
+        "Josephson constant (exact)",
+    ),
+    ("K_J", "", "=josephson_constant", ""),
+    (
+        "von_klitzing_constant",
+        "",
+        "=planck / electron_charge^2 to ohm",
+        "von Klitzing constant (exact)",
+    ),
+    ("R_K", "", "=von_klitzing_constant", ""),
+    (
+        "magnetic_flux_quantum",
+        "",
+        "=planck / (2 electron_charge) to Wb",
+        "magnetic flux quantum (exact)",
+    ),
+    ("Phi_0", "", "=magnetic_flux_quantum", ""),
+    (
+        "conductance_quantum",
+        "",
+        "=2 electron_charge^2 / planck to S",
+        "conductance quantum (exact)",
+    ),
+    ("G_0", "", "=conductance_quantum", ""),
+    (
+        "luminous_efficacy",
+        "",
+        "=683 lumen / W",
+        "luminous efficacy of 540 THz radiation (exact)",
+    ),
+    ("K_cd", "", "=luminous_efficacy", ""),
+    (
+        "caesium_frequency",
+        "",
+        "=9192631770 Hz",
+        "hyperfine transition frequency of caesium-133 (exact)",
+    ),
+    (
+        "fine_structure_constant",
+        "",
+        "=7.2973525693e-3",
+        "fine-structure constant",
+    ),
+    ("alpha", "", "=fine_structure_constant", ""),
+    (
+        "vacuum_permittivity",
+        "",
+        "=8.8541878128e-12 farad / m",
+        "vacuum electric permittivity",
+    ),
+    ("epsilon_0", "", "=vacuum_permittivity", ""),
+    ("\u{3b5}_0", "", "=vacuum_permittivity", ""),
+    (
+        "vacuum_permeability",
+        "",
+        "=1.25663706212e-6 N / A^2",
+        "vacuum magnetic permeability",
+    ),
+    ("mu_0", "", "=vacuum_permeability", ""),
+    ("\u{3bc}_0", "", "=vacuum_permeability", ""),
+    (
+        "vacuum_impedance",
+        "",
+        "=376.730313668 ohm",
+        "characteristic impedance of vacuum",
+    ),
+    ("Z_0", "", "=vacuum_impedance", ""),
+    ("electron_mass", "", "=9.1093837015e-31 kg", "electron mass"),
+    ("m_e", "", "=electron_mass", ""),
+    ("proton_mass", "", "=1.67262192369e-27 kg", "proton mass"),
+    ("m_p", "", "=proton_mass", ""),
+    ("neutron_mass", "", "=1.67492749804e-27 kg", "neutron mass"),
+    ("m_n", "", "=neutron_mass", ""),
+    ("muon_mass", "", "=1.883531627e-28 kg", "muon mass"),
+    ("tau_mass", "", "=3.16754e-27 kg", "tau mass"),
+    ("deuteron_mass", "", "=3.3435837724e-27 kg", "deuteron mass"),
+    (
+        "alpha_particle_mass",
+        "",
+        "=6.6446573357e-27 kg",
+        "alpha particle mass",
+    ),
+    (
+        "atomic_mass_constant",
+        "",
+        "=1.6605390666e-27 kg",
+        "atomic mass constant, 1/12 of the mass of carbon-12",
+    ),
+    ("m_u", "", "=atomic_mass_constant", ""),
+    (
+        "proton_electron_mass_ratio",
+        "",
+        "=1836.15267343",
+        "proton-electron mass ratio",
+    ),
+    (
+        "rydberg_constant",
+        "",
+        "=10973731.56816 / m",
+        "Rydberg constant",
+    ),
+    ("R_inf", "", "=rydberg_constant", ""),
+    ("bohr_radius", "", "=5.29177210903e-11 m", "Bohr radius"),
+    ("a_0", "", "=bohr_radius", ""),
+    (
+        "classical_electron_radius",
+        "",
+        "=2.8179403262e-15 m",
+        "classical electron radius",
+    ),
+    ("r_e", "", "=classical_electron_radius", ""),
+    (
+        "compton_wavelength",
+        "",
+        "=2.42631023867e-12 m",
+        "Compton wavelength of the electron",
+    ),
+    (
+        "hartree_energy",
+        "",
+        "=4.3597447222071e-18 J",
+        "Hartree energy",
+    ),
+    ("E_h", "", "=hartree_energy", ""),
+    (
+        "bohr_magneton",
+        "",
+        "=9.2740100783e-24 J / tesla",
+        "Bohr magneton",
+    ),
+    ("mu_B", "", "=bohr_magneton", ""),
+    (
+        "nuclear_magneton",
+        "",
+        "=5.0507837461e-27 J / tesla",
+        "nuclear magneton",
+    ),
+    ("mu_N", "", "=nuclear_magneton", ""),
     ("gravity", "", "=9.80665 m/s^2", ""),
     ("force", "", "gravity", ""), // used to convert some units
 ];
 
+const EXACT: &str = "exact";
+
+// Standard uncertainties of the CODATA 2018 recommended values above that
+// aren't marked as exact in their description
+const CONSTANT_UNCERTAINTIES: &[(&str, &str)] = &[
+    ("gravitational_constant", "1.5e-15 N m^2 / kg^2"),
+    ("wien_wavelength_displacement_constant", "1e-12 m K"),
+    ("fine_structure_constant", "1.1e-12"),
+    ("vacuum_permittivity", "1.3e-21 farad / m"),
+    ("vacuum_permeability", "1.9e-16 N / A^2"),
+    ("vacuum_impedance", "5.7e-8 ohm"),
+    ("electron_mass", "2.8e-40 kg"),
+    ("proton_mass", "5.1e-37 kg"),
+    ("neutron_mass", "9.5e-37 kg"),
+    ("muon_mass", "4.2e-36 kg"),
+    ("tau_mass", "2.1e-31 kg"),
+    ("deuteron_mass", "1e-36 kg"),
+    ("alpha_particle_mass", "2e-36 kg"),
+    ("atomic_mass_constant", "5e-37 kg"),
+    ("proton_electron_mass_ratio", "1.1e-7"),
+    ("rydberg_constant", "2.1e-5 / m"),
+    ("bohr_radius", "8e-21 m"),
+    ("classical_electron_radius", "1.3e-24 m"),
+    ("compton_wavelength", "7.3e-22 m"),
+    ("hartree_energy", "8.5e-30 J"),
+    ("bohr_magneton", "2.8e-33 J / tesla"),
+    ("nuclear_magneton", "1.5e-36 J / tesla"),
+];
+
+/// Returns the standard uncertainty of a physical constant, or `exact` if
+/// the constant is exact in the SI
+pub(crate) fn query_constant_uncertainty(name: &str) -> Option<&'static str> {
+    if CONSTANTS
+        .iter()
+        .any(|(constant, _, _, description)| *constant == name && description.ends_with("(exact)"))
+    {
+        return Some(EXACT);
+    }
+    CONSTANT_UNCERTAINTIES
+        .iter()
+        .find(|(constant, _)| *constant == name)
+        .map(|(_, uncertainty)| *uncertainty)
+}
+
 const ANGLES: &[UnitTuple] = &[
     ("radian", "radians", "l@1", ""),
     ("rad", "", "radian", ""),
@@ -352,11 +594,23 @@ const COMMON_SI_DERIVED_UNITS: &[UnitTuple] = &[
         "equivalent dose of ionising radiation",
     ),
     ("Sv", "", "s@sievert", ""),
-    ("rem", "", "l@1/100 Sv", ""),
+    (
+        "roentgen_equivalent_man",
+        "",
+        "l@1/100 Sv",
+        "older unit of equivalent dose",
+    ),
     ("roentgen", "roentgens", "l@0.000258 coulomb/kg", ""),
     ("R", "", "s@roentgen", ""),
 ];
 
+/// Named SI derived units that products of SI base units like `kg m / s^2`
+/// are simplified to
+pub(crate) const DERIVED_UNITS: &[&str] = &[
+    "newton", "pascal", "joule", "watt", "coulomb", "volt", "ohm", "siemens", "farad", "weber",
+    "tesla", "henry",
+];
+
 const TIME_UNITS: &[UnitTuple] = &[
     ("sec", "secs", "s@second", ""),
     ("minute", "minutes", "l@60 seconds", ""),
@@ -431,6 +685,24 @@ const RATIOS: &[UnitTuple] = &[
     ("bel", "bels", "0.5 * ln(10) neper", ""),
     ("decibel", "decibels", "1/10 bel", ""),
     ("dB", "", "decibel", ""),
+    // logarithmic levels relative to a reference quantity
+    ("dBm", "", "!", "power level relative to 1 milliwatt"),
+    ("dBW", "", "!", "power level relative to 1 watt"),
+    ("dBV", "", "!", "voltage level relative to 1 volt"),
+    (
+        "dB\u{b5}V",
+        "",
+        "!",
+        "voltage level relative to 1 microvolt",
+    ), // micro sign
+    ("dBuV", "", "dB\u{b5}V", ""),
+    (
+        "dB_SPL",
+        "",
+        "!",
+        "sound pressure level relative to 20 micropascals",
+    ),
+    ("dBSPL", "", "dB_SPL", ""),
     ("mill", "mills", "0.001", ""),
     ("ppm", "", "1e-6", ""),
     ("parts_per_million", "", "ppm", ""),
@@ -536,10 +808,88 @@ const LIQUID_UNITS: &[UnitTuple] = &[
     ("fluid_ounce", "", "1/16 pint", ""),
     ("fluid_dram", "", "1/8 floz", ""),
     ("qt", "", "quart", ""),
-    ("pt", "", "pint", ""),
     ("floz", "", "fluid_ounce", ""),
 ];
 
+// UK volumes differ from the US ones above, e.g. an imperial pint is 20
+// imperial fluid ounces instead of 16
+const IMPERIAL_LIQUID_UNITS: &[UnitTuple] = &[
+    (
+        "imperial_gallon",
+        "imperial_gallons",
+        "4.54609 L",
+        "UK gallon",
+    ),
+    ("uk_gallon", "uk_gallons", "imperial_gallon", ""),
+    (
+        "imperial_quart",
+        "imperial_quarts",
+        "1/4 imperial_gallon",
+        "UK quart",
+    ),
+    ("uk_quart", "uk_quarts", "imperial_quart", ""),
+    (
+        "imperial_pint",
+        "imperial_pints",
+        "1/2 imperial_quart",
+        "UK pint",
+    ),
+    ("uk_pint", "uk_pints", "imperial_pint", ""),
+    (
+        "imperial_gill",
+        "imperial_gills",
+        "1/4 imperial_pint",
+        "UK gill",
+    ),
+    (
+        "imperial_fluid_ounce",
+        "imperial_fluid_ounces",
+        "1/20 imperial_pint",
+        "UK fluid ounce",
+    ),
+    (
+        "uk_fluid_ounce",
+        "uk_fluid_ounces",
+        "imperial_fluid_ounce",
+        "",
+    ),
+    ("us_gallon", "us_gallons", "gallon", "US liquid gallon"),
+    ("us_quart", "us_quarts", "quart", "US liquid quart"),
+    ("us_pint", "us_pints", "pint", "US liquid pint"),
+    ("us_gill", "us_gills", "gill", "US gill"),
+    (
+        "us_fluid_ounce",
+        "us_fluid_ounces",
+        "fluid_ounce",
+        "US fluid ounce",
+    ),
+];
+
+const KITCHEN_UNITS: &[UnitTuple] = &[
+    ("cup", "cups", "8 fluid_ounce", "US customary cup"),
+    ("us_cup", "us_cups", "cup", ""),
+    ("tablespoon", "tablespoons", "1/16 cup", "US tablespoon"),
+    ("tbsp", "", "tablespoon", ""),
+    ("Tbsp", "", "tablespoon", ""),
+    ("teaspoon", "teaspoons", "1/3 tablespoon", "US teaspoon"),
+    ("tsp", "", "teaspoon", ""),
+    (
+        "legal_cup",
+        "legal_cups",
+        "240 mL",
+        "US cup used for nutrition labels",
+    ),
+    ("metric_cup", "metric_cups", "250 mL", ""),
+    ("metric_tablespoon", "metric_tablespoons", "15 mL", ""),
+    ("metric_teaspoon", "metric_teaspoons", "5 mL", ""),
+    (
+        "imperial_cup",
+        "imperial_cups",
+        "10 imperial_fluid_ounce",
+        "UK cup",
+    ),
+];
+
 const AVOIRDUPOIS_WEIGHT: &[UnitTuple] = &[
     ("pound", "pounds", "0.45359237 kg", ""),
     ("lb", "lbs", "pound", ""),
@@ -594,6 +944,127 @@ const IMPERIAL_ABBREVIATIONS: &[UnitTuple] = &[
     ("psi", "", "pound force / inch^2", ""),
 ];
 
+// centimetre-gram-second units, including the electromagnetic ones
+const CGS_UNITS: &[UnitTuple] = &[
+    ("dyne", "dynes", "l@g cm / s^2", "CGS unit of force"),
+    ("dyn", "", "s@dyne", ""),
+    ("erg", "ergs", "l@dyne cm", "CGS unit of energy"),
+    ("barye", "baryes", "l@dyne / cm^2", "CGS unit of pressure"),
+    (
+        "poise",
+        "",
+        "l@dyne s / cm^2",
+        "CGS unit of dynamic viscosity",
+    ),
+    (
+        "stokes",
+        "",
+        "l@cm^2 / s",
+        "CGS unit of kinematic viscosity",
+    ),
+    (
+        "galileo",
+        "galileos",
+        "l@cm / s^2",
+        "CGS unit of acceleration",
+    ),
+    ("Gal", "", "s@galileo", ""),
+    (
+        "gauss",
+        "",
+        "l@1e-4 tesla",
+        "CGS unit of magnetic flux density",
+    ),
+    (
+        "maxwell",
+        "maxwells",
+        "l@1e-8 Wb",
+        "CGS unit of magnetic flux",
+    ),
+    ("Mx", "", "s@maxwell", ""),
+    (
+        "oersted",
+        "",
+        "l@1000/(4 pi) A / m",
+        "CGS unit of magnetic field strength",
+    ),
+    ("Oe", "", "s@oersted", ""),
+    ("abampere", "abamperes", "l@10 A", "CGS unit of current"),
+    ("biot", "biots", "abampere", ""),
+    (
+        "statcoulomb",
+        "statcoulombs",
+        "l@1/2997924580 coulomb",
+        "CGS unit of charge",
+    ),
+    ("franklin", "franklins", "statcoulomb", ""),
+];
+
+const PRESSURE_UNITS: &[UnitTuple] = &[
+    ("torr", "", "l@1/760 atm", "about 1 mmHg"),
+    ("Torr", "", "torr", ""),
+    ("ksi", "", "1000 psi", "thousand pounds per square inch"),
+    (
+        "technical_atmosphere",
+        "technical_atmospheres",
+        "kgf / cm^2",
+        "",
+    ),
+    ("at", "", "technical_atmosphere", ""),
+    (
+        "inch_of_water",
+        "inches_of_water",
+        "inch gravity g / cm^3",
+        "",
+    ),
+    ("inH2O", "", "inch_of_water", ""),
+];
+
+const OTHER_ENGINEERING_UNITS: &[UnitTuple] = &[
+    ("kgf", "", "kg force", "kilogram-force"),
+    ("VA", "", "s@V A", "volt-ampere, unit of apparent power"),
+    ("baud", "", "l@1 / s", "symbols per second"),
+    ("Bd", "", "s@baud", ""),
+    ("foot_candle", "foot_candles", "lumen / ft^2", "illuminance"),
+    ("fc", "", "foot_candle", ""),
+    (
+        "foot_lambert",
+        "foot_lamberts",
+        "1/pi candela / ft^2",
+        "luminance",
+    ),
+];
+
+// `pixel` and `em` depend on the pixel density and base font size set in
+// the context. `dp` is not available since it means decimal places.
+const TYPOGRAPHY_UNITS: &[UnitTuple] = &[
+    ("pt", "", "point", ""),
+    ("pica", "picas", "12 point", ""),
+    (
+        "pixel",
+        "pixels",
+        "$PIXEL",
+        "CSS pixel, 1/96 inch unless the pixel density is changed",
+    ),
+    ("px", "", "pixel", ""),
+    (
+        "density_independent_pixel",
+        "density_independent_pixels",
+        "1/160 inch",
+        "Android pixel, which does not depend on the pixel density",
+    ),
+    ("dip", "", "density_independent_pixel", ""),
+    (
+        "em",
+        "",
+        "$FONT_SIZE",
+        "font size, 16 px unless the base font size is changed",
+    ),
+    ("rem", "", "em", "font size of the root element"),
+    ("dpi", "", "1 / inch", "dots per inch"),
+    ("ppi", "", "dpi", "pixels per inch"),
+];
+
 const NAUTICAL_UNITS: &[UnitTuple] = &[
     ("fathom", "fathoms", "6 ft", ""),
     ("nautical_mile", "nautical_miles", "1852 m", ""),
@@ -613,6 +1084,7 @@ const CURRENCIES: &[UnitTuple] = &[
     ("euro", "euros", "EUR", ""),
     ("\u{20ac}", "\u{20ac}", "EUR", ""), // Euro symbol
     ("\u{a3}", "\u{a3}", "GBP", ""),
+    ("\u{a5}", "\u{a5}", "JPY", ""), // Yen symbol
     ("AU$", "AU$", "AUD", ""),
     ("HK$", "HK$", "HKD", ""),
     ("NZ$", "NZ$", "NZD", ""),
@@ -636,6 +1108,144 @@ const CURRENCY_IDENTIFIERS: &[&str] = &[
     "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWL",
 ];
 
+// ISO 4217 code, number of minor unit digits (`None` if amounts should not be
+// rounded, e.g. for precious metals), symbol, whether the symbol precedes the
+// amount, and the decimal separator used together with the symbol.
+// Currencies that aren't listed here have 2 minor unit digits and no symbol.
+type CurrencyTuple = (&'static str, Option<usize>, &'static str, bool, char);
+
+const CURRENCY_METADATA: &[CurrencyTuple] = &[
+    ("BHD", Some(3), "", false, '.'),
+    ("BIF", Some(0), "", false, '.'),
+    ("CLF", Some(4), "", false, '.'),
+    ("CLP", Some(0), "", false, '.'),
+    ("DJF", Some(0), "", false, '.'),
+    ("EUR", Some(2), "\u{20ac}", false, ','),
+    ("GBP", Some(2), "\u{a3}", true, '.'),
+    ("GNF", Some(0), "", false, '.'),
+    ("IQD", Some(3), "", false, '.'),
+    ("ISK", Some(0), "", false, '.'),
+    ("JOD", Some(3), "", false, '.'),
+    ("JPY", Some(0), "\u{a5}", true, '.'),
+    ("KMF", Some(0), "", false, '.'),
+    ("KRW", Some(0), "", false, '.'),
+    ("KWD", Some(3), "", false, '.'),
+    ("LYD", Some(3), "", false, '.'),
+    ("OMR", Some(3), "", false, '.'),
+    ("PYG", Some(0), "", false, '.'),
+    ("RWF", Some(0), "", false, '.'),
+    ("TND", Some(3), "", false, '.'),
+    ("UGX", Some(0), "", false, '.'),
+    ("USD", Some(2), "$", true, '.'),
+    ("UYI", Some(0), "", false, '.'),
+    ("UYW", Some(4), "", false, '.'),
+    ("VND", Some(0), "", false, '.'),
+    ("VUV", Some(0), "", false, '.'),
+    ("XAF", Some(0), "", false, '.'),
+    ("XAG", None, "", false, '.'),
+    ("XAU", None, "", false, '.'),
+    ("XBA", None, "", false, '.'),
+    ("XBB", None, "", false, '.'),
+    ("XBC", None, "", false, '.'),
+    ("XBD", None, "", false, '.'),
+    ("XDR", None, "", false, '.'),
+    ("XOF", Some(0), "", false, '.'),
+    ("XPD", None, "", false, '.'),
+    ("XPF", Some(0), "", false, '.'),
+    ("XPT", None, "", false, '.'),
+    ("XSU", None, "", false, '.'),
+    ("XTS", None, "", false, '.'),
+    ("XUA", None, "", false, '.'),
+    ("XXX", None, "", false, '.'),
+];
+
+/// How amounts of a particular currency unit should be printed
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct CurrencyFormat {
+    pub(crate) minor_digits: Option<usize>,
+    /// whether the unit is the currency symbol, e.g. `$` rather than `USD`
+    pub(crate) is_symbol: bool,
+    pub(crate) symbol_first: bool,
+    pub(crate) decimal_separator: char,
+}
+
+/// Returns formatting information if the given unit name refers to a
+/// currency, either by its ISO 4217 code (e.g. `EUR`) or by one of the
+/// names in `CURRENCIES` (e.g. `euros` or `€`)
+pub(crate) fn query_currency_format(name: &str) -> Option<CurrencyFormat> {
+    let code = if CURRENCY_IDENTIFIERS.binary_search(&name).is_ok() {
+        name
+    } else {
+        CURRENCIES
+            .iter()
+            .find(|(s, p, _, _)| *s == name || *p == name)
+            .map(|(_, _, def, _)| *def)
+            .filter(|def| CURRENCY_IDENTIFIERS.binary_search(def).is_ok())?
+    };
+    Some(
+        match CURRENCY_METADATA.binary_search_by_key(&code, |metadata| metadata.0) {
+            Ok(idx) => {
+                let (_, minor_digits, symbol, symbol_first, decimal_separator) =
+                    CURRENCY_METADATA[idx];
+                let is_symbol = symbol == name;
+                CurrencyFormat {
+                    minor_digits,
+                    is_symbol,
+                    symbol_first: is_symbol && symbol_first,
+                    decimal_separator: if is_symbol { decimal_separator } else { '.' },
+                }
+            }
+            Err(_) => CurrencyFormat {
+                minor_digits: Some(2),
+                is_symbol: false,
+                symbol_first: false,
+                decimal_separator: '.',
+            },
+        },
+    )
+}
+
+// Typical densities of common substances, so that e.g. `1 cup flour` can
+// be converted to grams. Dry ingredients are measured loosely packed.
+const SUBSTANCE_DENSITIES: &[(&str, &str)] = &[
+    ("air", "approx. 1.204 kg / m^3"),
+    ("brown_sugar", "approx. 220 g / cup"),
+    ("butter", "approx. 227 g / cup"),
+    ("concrete", "approx. 2400 kg / m^3"),
+    ("diesel", "approx. 0.832 kg / L"),
+    ("ethanol", "approx. 0.789 g / mL"),
+    ("flour", "approx. 125 g / cup"),
+    ("gasoline", "approx. 0.745 kg / L"),
+    ("honey", "approx. 1.42 g / mL"),
+    ("ice", "approx. 0.917 g / mL"),
+    ("milk", "approx. 1.03 g / mL"),
+    ("olive_oil", "approx. 0.911 g / mL"),
+    ("petrol", "approx. 0.745 kg / L"),
+    ("rice", "approx. 185 g / cup"),
+    ("salt", "approx. 273 g / cup"),
+    ("seawater", "approx. 1.025 g / mL"),
+    ("steel", "approx. 7.85 g / cm^3"),
+    ("sugar", "approx. 200 g / cup"),
+    ("water", "approx. 1 g / mL"),
+];
+
+/// Returns whether a unit like `meter` has a symbol that can be used with
+/// short prefixes, like `m`
+pub(crate) fn has_prefixable_symbol(name: &str) -> bool {
+    ALL_UNIT_DEFS
+        .iter()
+        .flat_map(|group| group.iter())
+        .any(|(_, _, definition, _)| definition.strip_prefix("s@") == Some(name))
+}
+
+/// Returns the density of a substance like `flour` or `gasoline`
+pub(crate) fn query_substance_density(name: &str) -> Option<&'static str> {
+    SUBSTANCE_DENSITIES
+        .binary_search_by_key(&name, |(substance, _)| substance)
+        .ok()
+        .map(|idx| SUBSTANCE_DENSITIES[idx].1)
+}
+
 pub(crate) const ALL_UNIT_DEFS: &[&[UnitTuple]] = &[
     BASE_UNITS,
     BASE_UNIT_ABBREVIATIONS,
@@ -654,10 +1264,16 @@ pub(crate) const ALL_UNIT_DEFS: &[&[UnitTuple]] = &[
     COMMON_PHYSICAL_UNITS,
     IMPERIAL_UNITS,
     LIQUID_UNITS,
+    IMPERIAL_LIQUID_UNITS,
+    KITCHEN_UNITS,
     AVOIRDUPOIS_WEIGHT,
     TROY_WEIGHT,
     OTHER_WEIGHTS,
     IMPERIAL_ABBREVIATIONS,
+    CGS_UNITS,
+    PRESSURE_UNITS,
+    OTHER_ENGINEERING_UNITS,
+    TYPOGRAPHY_UNITS,
     NAUTICAL_UNITS,
     CURRENCIES,
 ];
@@ -695,54 +1311,219 @@ const SHORT_PREFIXES: &[(&str, &str)] = &[
     ("y", "sp@yocto"),
 ];
 
+/// Finds a unit by its name. If there is no exact match and `case_sensitive`
+/// is false, a unit with a different capitalization is returned, or an
+/// error listing the candidates if more than one unit matches.
 #[allow(clippy::too_many_lines)]
 pub(crate) fn query_unit<'a>(
     ident: &'a str,
     short_prefixes: bool,
     case_sensitive: bool,
-) -> Option<(&'static str, &'static str, &'static str)> {
+) -> Result<Option<(&'static str, &'static str, &'static str)>, FendError> {
     if short_prefixes {
         for (name, def) in SHORT_PREFIXES {
             if *name == ident {
-                return Some((name, name, def));
+                return Ok(Some((name, name, def)));
             }
         }
     }
     if ident == "USD" {
-        return Some(("USD", "USD", "!"));
+        return Ok(Some(("USD", "USD", "!")));
     }
     if let Ok(idx) = CURRENCY_IDENTIFIERS.binary_search(&ident) {
         let name = CURRENCY_IDENTIFIERS[idx];
-        return Some((name, name, "$CURRENCY"));
+        return Ok(Some((name, name, "$CURRENCY")));
     }
-    let mut candidates = vec![];
+    let mut candidates: Vec<(UnitDef, &str)> = vec![];
     for group in ALL_UNIT_DEFS {
         for def in *group {
+            let description = def.3;
             let def = UnitDef {
                 singular: def.0,
                 plural: if def.1.is_empty() { def.0 } else { def.1 },
                 definition: def.2,
             };
             if def.singular == ident || def.plural == ident {
-                return Some((def.singular, def.plural, def.definition));
+                return Ok(Some((def.singular, def.plural, def.definition)));
             }
             if !case_sensitive
                 && (def.singular.eq_ignore_ascii_case(ident)
                     || def.plural.eq_ignore_ascii_case(ident))
             {
-                candidates.push(Some((def.singular, def.plural, def.definition)));
+                candidates.push((def, description));
             }
         }
     }
-    if candidates.len() == 1 {
-        return candidates[0];
+    // aliases like `Torr` for `torr`, or units that are defined twice,
+    // refer to the same unit and aren't ambiguous
+    let canonical_name = |def: &UnitDef| {
+        let definition = strip_prefix_rule(def.definition).trim();
+        if definition.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            definition
+        } else {
+            def.singular
+        }
+    };
+    let mut distinct: Vec<(&UnitDef, &str)> = vec![];
+    for (def, description) in &candidates {
+        if !distinct
+            .iter()
+            .any(|(other, _)| canonical_name(other) == canonical_name(def))
+        {
+            distinct.push((def, description));
+        }
+    }
+    match distinct.as_slice() {
+        [] => Ok(None),
+        [(def, _)] => Ok(Some((def.singular, def.plural, def.definition))),
+        _ => Err(FendError::AmbiguousUnit(
+            ident.to_string(),
+            distinct
+                .iter()
+                .map(|(def, description)| {
+                    let description = if description.is_empty() {
+                        strip_prefix_rule(def.definition).trim()
+                    } else {
+                        description
+                    };
+                    (def.singular.to_string(), description.to_string())
+                })
+                .collect(),
+        )),
+    }
+}
+
+pub(crate) struct UnitDocumentation {
+    pub(crate) singular: &'static str,
+    pub(crate) definition: &'static str,
+    pub(crate) description: &'static str,
+    pub(crate) aliases: Vec<&'static str>,
+}
+
+fn all_unit_tuples() -> impl Iterator<Item = UnitTuple> {
+    ALL_UNIT_DEFS
+        .iter()
+        .flat_map(|group| group.iter().copied())
+        .chain(
+            SHORT_PREFIXES
+                .iter()
+                .map(|&(name, def)| (name, "", def, "")),
+        )
+        .map(|(s, p, d, desc)| (s, if p.is_empty() { s } else { p }, d, desc))
+}
+
+fn find_unit_tuple(ident: &str) -> Option<UnitTuple> {
+    if ident == "USD" {
+        return Some(("USD", "USD", "!", "currency"));
+    }
+    if let Ok(idx) = CURRENCY_IDENTIFIERS.binary_search(&ident) {
+        let name = CURRENCY_IDENTIFIERS[idx];
+        return Some((name, name, "$CURRENCY", "currency"));
+    }
+    if let Some(def) = all_unit_tuples().find(|def| def.0 == ident || def.1 == ident) {
+        return Some(def);
+    }
+    let mut candidates = all_unit_tuples()
+        .filter(|def| def.0.eq_ignore_ascii_case(ident) || def.1.eq_ignore_ascii_case(ident));
+    match (candidates.next(), candidates.next()) {
+        (Some(def), None) => Some(def),
+        _ => None,
     }
-    None
+}
+
+/// Removes prefix rule markers like `l@` from a definition
+pub(crate) fn strip_prefix_rule(definition: &str) -> &str {
+    for marker in ["lp@", "sp@", "l@", "s@", "="] {
+        if let Some(definition) = definition.strip_prefix(marker) {
+            return definition;
+        }
+    }
+    definition
+}
+
+/// Finds the definition, description and aliases of a unit. Aliases without
+/// their own description (e.g. `N` for `newton`) are resolved to the unit
+/// they refer to.
+pub(crate) fn query_unit_documentation(ident: &str) -> Option<UnitDocumentation> {
+    let mut def = find_unit_tuple(ident)?;
+    // limit the number of steps in case of cyclic aliases
+    for _ in 0..10 {
+        if !def.3.is_empty() {
+            break;
+        }
+        match find_unit_tuple(strip_prefix_rule(def.2).trim()) {
+            Some(target) if target.0 != def.0 => def = target,
+            _ => break,
+        }
+    }
+    let (singular, plural, definition, description) = def;
+    let mut aliases = vec![];
+    if plural != singular {
+        aliases.push(plural);
+    }
+    for (alias, _, alias_def, _) in all_unit_tuples() {
+        let alias_def = strip_prefix_rule(alias_def).trim();
+        if alias != singular
+            && (alias_def == singular || alias_def == plural)
+            && !aliases.contains(&alias)
+        {
+            aliases.push(alias);
+        }
+    }
+    Some(UnitDocumentation {
+        singular,
+        definition,
+        description,
+        aliases,
+    })
 }
 
 /// used for implicit unit addition, e.g. 5'5 -> 5'5"
 pub(crate) const IMPLICIT_UNIT_MAP: &[(&str, &str)] = &[("'", "\""), ("foot", "inches")];
 
+type Dimension = &'static [(&'static str, i64)];
+
+/// Units used for results with compound units in the metric and imperial
+/// unit systems, by dimension. The first unit that keeps the number at
+/// least 1 is used, or the last unit if there is none.
+pub(crate) const PREFERRED_UNITS: &[(Dimension, &[&str], &[&str])] = &[
+    (
+        &[("meter", 1)],
+        &["km", "m", "cm", "mm"],
+        &["mi", "ft", "inch"],
+    ),
+    (
+        &[("meter", 2)],
+        &["km^2", "m^2", "cm^2"],
+        &["mi^2", "acre", "ft^2", "inch^2"],
+    ),
+    (&[("meter", 3)], &["m^3", "L", "mL"], &["gal", "floz"]),
+    (&[("kilogram", 1)], &["kg", "g", "mg"], &["lb", "oz"]),
+    (&[("meter", 1), ("second", -1)], &["km/h"], &["mph"]),
+    (&[("meter", 1), ("second", -2)], &["m/s^2"], &["ft/s^2"]),
+    (
+        &[("kilogram", 1), ("meter", 1), ("second", -2)],
+        &["N"],
+        &["lbf"],
+    ),
+    (
+        &[("kilogram", 1), ("meter", 2), ("second", -2)],
+        &["MJ", "kJ", "J"],
+        &["BTU"],
+    ),
+    (
+        &[("kilogram", 1), ("meter", 2), ("second", -3)],
+        &["kW", "W"],
+        &["hp"],
+    ),
+    (
+        &[("kilogram", 1), ("meter", -1), ("second", -2)],
+        &["kPa", "Pa"],
+        &["psi"],
+    ),
+    (&[("kilogram", 1), ("meter", -3)], &["kg/m^3"], &["lb/ft^3"]),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -771,6 +1552,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn substances_sorted() {
+        let substances: Vec<_> = SUBSTANCE_DENSITIES.iter().map(|s| s.0).collect();
+        let mut sorted = substances.clone();
+        sorted.sort_unstable();
+        assert_eq!(substances, sorted, "substances are not sorted");
+    }
+
     #[test]
     fn currencies_sorted() {
         let currencies = CURRENCY_IDENTIFIERS.to_vec();
@@ -778,4 +1567,41 @@ mod tests {
         sorted.sort_unstable();
         assert_eq!(currencies, sorted, "currencies are not sorted");
     }
+
+    #[test]
+    fn currency_metadata_sorted() {
+        let codes: Vec<_> = CURRENCY_METADATA
+            .iter()
+            .map(|metadata| metadata.0)
+            .collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        assert_eq!(codes, sorted, "currency metadata is not sorted");
+        for code in codes {
+            assert!(CURRENCY_IDENTIFIERS.binary_search(&code).is_ok());
+        }
+    }
+
+    #[test]
+    fn constants_have_uncertainties() {
+        for (name, _, definition, description) in CONSTANTS {
+            if description.is_empty() {
+                // aliases and conventional values
+                continue;
+            }
+            assert!(
+                query_constant_uncertainty(name).is_some(),
+                "{name} ({definition}) has no uncertainty"
+            );
+        }
+        for (name, _) in CONSTANT_UNCERTAINTIES {
+            assert!(
+                CONSTANTS
+                    .iter()
+                    .any(|(constant, _, _, description)| constant == name
+                        && !description.ends_with("(exact)")),
+                "{name} is not a measured constant"
+            );
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::ast::Bop;
 use crate::date::{Date, DayOfWeek, Month};
 use crate::error::{FendError, Interrupt};
-use crate::num::{Base, FormattingStyle, Number};
+use crate::num::{Base, FormattingStyle, Modular, Number};
 use crate::scope::Scope;
 use crate::serialize::{
     deserialize_bool, deserialize_string, deserialize_u8, deserialize_usize, serialize_bool,
@@ -37,6 +37,21 @@ pub(crate) enum Value {
     Month(date::Month),
     DayOfWeek(date::DayOfWeek),
     Date(date::Date),
+    // built-in function with more than one argument, together with
+    // the arguments that have been applied so far
+    PartialApplication(BuiltInFunction, Vec<Self>),
+    // integer in the ring of integers modulo n, e.g. `5 (mod 13)`
+    Modular(Modular),
+    // list of values, e.g. cash flows in `npv 10% [-100, 60, 60]`
+    List(Vec<Self>),
+    // value split across several units, e.g. `5 ft 9 inches`, together
+    // with the original value
+    Compound(Box<Number>, Vec<Number>),
+    // number followed by a note on how it was calculated, e.g. when
+    // converting between reciprocal units like `mpg` and `L / 100 km`
+    NumWithNote(Box<Number>, Cow<'static, str>),
+    // substance with a typical density, e.g. `flour` in `1 cup flour`
+    Substance(Box<Number>),
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -107,6 +122,42 @@ impl Value {
                 serialize_u8(13, write)?;
                 d.serialize(write)?;
             }
+            Self::PartialApplication(f, args) => {
+                serialize_u8(14, write)?;
+                f.serialize(write)?;
+                serialize_usize(args.len(), write)?;
+                for arg in args {
+                    arg.serialize(write)?;
+                }
+            }
+            Self::Modular(m) => {
+                serialize_u8(15, write)?;
+                m.serialize(write)?;
+            }
+            Self::List(items) => {
+                serialize_u8(16, write)?;
+                serialize_usize(items.len(), write)?;
+                for item in items {
+                    item.serialize(write)?;
+                }
+            }
+            Self::Compound(n, parts) => {
+                serialize_u8(17, write)?;
+                n.serialize(write)?;
+                serialize_usize(parts.len(), write)?;
+                for part in parts {
+                    part.serialize(write)?;
+                }
+            }
+            Self::NumWithNote(n, note) => {
+                serialize_u8(18, write)?;
+                n.serialize(write)?;
+                serialize_string(note, write)?;
+            }
+            Self::Substance(density) => {
+                serialize_u8(19, write)?;
+                density.serialize(write)?;
+            }
         }
         Ok(())
     }
@@ -145,25 +196,60 @@ impl Value {
             11 => Self::Month(Month::deserialize(read)?),
             12 => Self::DayOfWeek(DayOfWeek::deserialize(read)?),
             13 => Self::Date(Date::deserialize(read)?),
+            14 => Self::PartialApplication(BuiltInFunction::deserialize(read)?, {
+                let len = deserialize_usize(read)?;
+                let mut v = Vec::with_capacity(len);
+                for _ in 0..len {
+                    v.push(Self::deserialize(read)?);
+                }
+                v
+            }),
+            15 => Self::Modular(Modular::deserialize(read)?),
+            16 => Self::List({
+                let len = deserialize_usize(read)?;
+                let mut v = Vec::with_capacity(len);
+                for _ in 0..len {
+                    v.push(Self::deserialize(read)?);
+                }
+                v
+            }),
+            17 => Self::Compound(Box::new(Number::deserialize(read)?), {
+                let len = deserialize_usize(read)?;
+                let mut v = Vec::with_capacity(len);
+                for _ in 0..len {
+                    v.push(Number::deserialize(read)?);
+                }
+                v
+            }),
+            18 => Self::NumWithNote(
+                Box::new(Number::deserialize(read)?),
+                Cow::Owned(deserialize_string(read)?),
+            ),
+            19 => Self::Substance(Box::new(Number::deserialize(read)?)),
             _ => return Err(FendError::DeserializationError),
         })
     }
 
     pub(crate) fn type_name(&self) -> &'static str {
         match self {
-            Self::Num(_) => "number",
-            Self::BuiltInFunction(_) | Self::Fn(_, _, _) => "function",
+            Self::BuiltInFunction(_) | Self::Fn(_, _, _) | Self::PartialApplication(_, _) => {
+                "function"
+            }
             Self::Format(_) => "formatting style",
             Self::Dp => "decimal places",
             Self::Sf => "significant figures",
             Self::Base(_) => "base",
             Self::Object(_) => "object",
+            Self::Substance(_) => "substance",
             Self::String(_) => "string",
             Self::Bool(_) => "bool",
             Self::Unit => "()",
             Self::Month(_) => "month",
             Self::DayOfWeek(_) => "day of week",
             Self::Date(_) => "date",
+            Self::Modular(_) => "modular integer",
+            Self::List(_) => "list",
+            Self::Num(_) | Self::Compound(_, _) | Self::NumWithNote(_, _) => "number",
         }
     }
 
@@ -177,11 +263,22 @@ impl Value {
 
     pub(crate) fn expect_num(self) -> Result<Number, FendError> {
         match self {
-            Self::Num(bigrat) => Ok(*bigrat),
+            Self::Num(bigrat) | Self::Compound(bigrat, _) | Self::NumWithNote(bigrat, _) => {
+                Ok(*bigrat)
+            }
             _ => Err(FendError::ExpectedANumber),
         }
     }
 
+    /// Drops the note from a value like `0.02 s (converted via reciprocal)`
+    /// once it is used as part of a larger expression
+    pub(crate) fn without_note(self) -> Self {
+        match self {
+            Self::Compound(n, _) | Self::NumWithNote(n, _) => Self::Num(n),
+            v => v,
+        }
+    }
+
     pub(crate) fn is_unit(&self) -> bool {
         matches!(self, Self::Unit)
     }
@@ -222,6 +319,35 @@ impl Value {
         })
     }
 
+    /// Applies a binary operator where at least one operand is a modular
+    /// integer. Plain integers are converted into the same ring.
+    pub(crate) fn modular_bop<I: Interrupt>(
+        self,
+        bop: Bop,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let (a, b) = match (self, rhs) {
+            (Self::Modular(a), Self::Num(b)) if bop == Bop::Pow => {
+                return Ok(Self::Modular(a.pow(*b, int)?));
+            }
+            (Self::Modular(a), Self::Modular(b)) => (a, b),
+            (Self::Modular(a), Self::Num(b)) => {
+                let b = a.with_value(*b, int)?;
+                (a, b)
+            }
+            (Self::Num(a), Self::Modular(b)) => (b.with_value(*a, int)?, b),
+            _ => return Err(FendError::ExpectedANumber),
+        };
+        Ok(Self::Modular(match bop {
+            Bop::Plus | Bop::ImplicitPlus => a.add(&b, int)?,
+            Bop::Minus => a.sub(&b, int)?,
+            Bop::Mul => a.mul(&b, int)?,
+            Bop::Div => a.div(&b, int)?,
+            _ => return Err(FendError::UnsupportedModularOperation),
+        }))
+    }
+
     pub(crate) fn apply<I: Interrupt>(
         self,
         other: Expr,
@@ -245,6 +371,13 @@ impl Value {
                     }
                     return Ok(Self::Format(FormattingStyle::SignificantFigures(num)));
                 }
+                if let Self::Modular(_) = other {
+                    return Self::Num(n).modular_bop(Bop::Mul, other, int);
+                }
+                if let Some(density) = other.substance_density() {
+                    // e.g. `1 cup flour`
+                    return Ok(Self::Num(Box::new(n.apply_density(density, int)?)));
+                }
                 if apply_mul_handling == ApplyMulHandling::OnlyApply {
                     let self_ = Self::Num(n);
                     return Err(FendError::IsNotAFunction(
@@ -261,10 +394,17 @@ impl Value {
             Self::BuiltInFunction(func) => {
                 Self::apply_built_in_function(func, other, scope, context, int)?
             }
+            Self::PartialApplication(func, args) => {
+                Self::apply_partially(func, args, other, scope, context, int)?
+            }
             Self::Fn(param, expr, custom_scope) => {
                 let new_scope = Scope::with_variable(param, other, scope, custom_scope);
                 return crate::ast::evaluate(*expr, Some(Arc::new(new_scope)), context, int);
             }
+            Self::Modular(m) if apply_mul_handling == ApplyMulHandling::Both => {
+                let other = crate::ast::evaluate(other, scope, context, int)?;
+                Self::Modular(m).modular_bop(Bop::Mul, other, int)?
+            }
             _ => return Err(FendError::IsNotAFunctionOrNumber(stringified_self)),
         })
     }
@@ -306,6 +446,89 @@ impl Value {
             BuiltInFunction::Sample => arg.expect_num()?.sample(context, int)?,
             BuiltInFunction::Not => return Ok(Self::Bool(!arg.as_bool()?)),
             BuiltInFunction::Conjugate => arg.expect_num()?.conjugate()?,
+            BuiltInFunction::Arg => arg.expect_num()?.arg(int)?,
+            BuiltInFunction::Irr => crate::finance::irr(arg, context, int)?,
+            BuiltInFunction::MolarMass => crate::chemistry::molar_mass(arg, context, int)?,
+            BuiltInFunction::Integrate
+            | BuiltInFunction::Derivative
+            | BuiltInFunction::Sum
+            | BuiltInFunction::Product
+            | BuiltInFunction::Pmt
+            | BuiltInFunction::Pv
+            | BuiltInFunction::Fv
+            | BuiltInFunction::Compound
+            | BuiltInFunction::Npv => return Ok(Self::PartialApplication(func, vec![arg])),
+        })))
+    }
+
+    fn apply_partially<I: Interrupt>(
+        func: BuiltInFunction,
+        mut args: Vec<Self>,
+        arg: Expr,
+        scope: Option<Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        args.push(crate::ast::evaluate(arg, scope, context, int)?);
+        if args.len() < func.arity() {
+            return Ok(Self::PartialApplication(func, args));
+        }
+        Ok(Self::Num(Box::new(match (func, args.as_slice()) {
+            (BuiltInFunction::Integrate, [f, a, b]) => crate::numerical::integrate(
+                f,
+                a.clone().expect_num()?,
+                b.clone().expect_num()?,
+                context,
+                int,
+            )?,
+            (BuiltInFunction::Derivative, [f, x]) => {
+                crate::numerical::derivative(f, &x.clone().expect_num()?, context, int)?
+            }
+            (BuiltInFunction::Sum, [f, start, end]) => crate::numerical::sum(
+                f,
+                start.clone().expect_num()?,
+                end.clone().expect_num()?,
+                context,
+                int,
+            )?,
+            (BuiltInFunction::Product, [f, start, end]) => crate::numerical::product(
+                f,
+                start.clone().expect_num()?,
+                end.clone().expect_num()?,
+                context,
+                int,
+            )?,
+            (BuiltInFunction::Pmt, [rate, periods, principal]) => crate::finance::pmt(
+                rate.clone().expect_num()?,
+                periods.clone().expect_num()?,
+                principal.clone().expect_num()?,
+                int,
+            )?,
+            (BuiltInFunction::Pv, [rate, periods, payment]) => crate::finance::pv(
+                rate.clone().expect_num()?,
+                periods.clone().expect_num()?,
+                payment.clone().expect_num()?,
+                int,
+            )?,
+            (BuiltInFunction::Fv, [rate, periods, payment]) => crate::finance::fv(
+                rate.clone().expect_num()?,
+                periods.clone().expect_num()?,
+                payment.clone().expect_num()?,
+                int,
+            )?,
+            (BuiltInFunction::Compound, [principal, rate, frequency, time]) => {
+                crate::finance::compound(
+                    principal.clone().expect_num()?,
+                    rate.clone().expect_num()?,
+                    frequency.clone().expect_num()?,
+                    time.clone().expect_num()?,
+                    int,
+                )?
+            }
+            (BuiltInFunction::Npv, [rate, cash_flows]) => {
+                crate::finance::npv(rate.clone().expect_num()?, cash_flows.clone(), int)?
+            }
+            _ => return Err(FendError::IsNotAFunction(func.to_string())),
         })))
     }
 
@@ -324,6 +547,7 @@ impl Value {
         Ok(res)
     }
 
+    #[allow(clippy::too_many_lines)]
     pub(crate) fn format<I: Interrupt>(
         &self,
         indent: usize,
@@ -424,10 +648,92 @@ impl Value {
                 string: d.to_string(),
                 kind: crate::SpanKind::Date,
             }),
+            Self::PartialApplication(func, args) => {
+                spans.push(Span {
+                    string: func.to_string(),
+                    kind: SpanKind::BuiltInFunction,
+                });
+                for arg in args {
+                    spans.push(Span::from_string(" (".to_string()));
+                    arg.format(indent, spans, ctx, int)?;
+                    spans.push(Span::from_string(")".to_string()));
+                }
+            }
+            Self::Modular(m) => {
+                m.value().format(ctx, int)?.spans(spans);
+                spans.push(Span::from_string(" (".to_string()));
+                spans.push(Span {
+                    string: "mod".to_string(),
+                    kind: SpanKind::Keyword,
+                });
+                spans.push(Span::from_string(" ".to_string()));
+                m.modulus().format(ctx, int)?.spans(spans);
+                spans.push(Span::from_string(")".to_string()));
+            }
+            Self::List(items) => {
+                spans.push(Span::from_string("[".to_string()));
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        spans.push(Span::from_string(", ".to_string()));
+                    }
+                    item.format(indent, spans, ctx, int)?;
+                }
+                spans.push(Span::from_string("]".to_string()));
+            }
+            Self::Compound(_, parts) => Self::format_compound(parts, spans, ctx, int)?,
+            Self::NumWithNote(n, note) => {
+                n.clone().simplify(int)?.format(ctx, int)?.spans(spans);
+                spans.push(Span::from_string(format!(" ({note})")));
+            }
+            Self::Substance(density) => {
+                Self::substance_as_object(density).format(indent, spans, ctx, int)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn format_compound<I: Interrupt>(
+        parts: &[Number],
+        spans: &mut Vec<Span>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<(), FendError> {
+        let parts = parts
+            .iter()
+            .map(|part| part.clone().simplify(int)?.format(ctx, int))
+            .collect::<Result<Vec<_>, _>>()?;
+        // only print "approx." once at the start, e.g. `approx. 10 ft 3.68 inches`
+        if parts.iter().any(|part| !part.is_exact()) {
+            spans.push(Span {
+                string: "approx. ".to_string(),
+                kind: SpanKind::Ident,
+            });
+        }
+        for (i, part) in parts.into_iter().enumerate() {
+            if i != 0 {
+                spans.push(Span::from_string(" ".to_string()));
+            }
+            part.into_exact().spans(spans);
         }
         Ok(())
     }
 
+    /// Returns the density if this is a substance, like `flour`
+    pub(crate) fn substance_density(&self) -> Option<Number> {
+        match self {
+            Self::Substance(density) => Some((**density).clone()),
+            _ => None,
+        }
+    }
+
+    /// Substances are shown and accessed like an object with a density
+    fn substance_as_object(density: &Number) -> Self {
+        Self::Object(vec![(
+            "density".into(),
+            Box::new(Self::Num(Box::new(density.clone()))),
+        )])
+    }
+
     pub(crate) fn get_object_member(self, key: &Ident) -> Result<Self, FendError> {
         match self {
             Self::Object(kv) => {
@@ -439,6 +745,7 @@ impl Value {
                 Err(FendError::CouldNotFindKeyInObject)
             }
             Self::Date(d) => d.get_object_member(key),
+            Self::Substance(density) => Self::substance_as_object(&density).get_object_member(key),
             _ => Err(FendError::ExpectedAnObject),
         }
     }
@@ -473,6 +780,14 @@ impl fmt::Debug for Value {
             Self::Month(m) => write!(f, "{m}"),
             Self::DayOfWeek(d) => write!(f, "{d}"),
             Self::Date(d) => write!(f, "{d:?}"),
+            Self::PartialApplication(func, args) => {
+                write!(f, "built-in function: {} {args:?}", func.as_str())
+            }
+            Self::Modular(m) => write!(f, "{m:?}"),
+            Self::List(items) => write!(f, "{items:?}"),
+            Self::Compound(_, parts) => write!(f, "{parts:?}"),
+            Self::NumWithNote(n, note) => write!(f, "{n:?} ({note})"),
+            Self::Substance(density) => write!(f, "substance: {density:?}"),
         }
     }
 }
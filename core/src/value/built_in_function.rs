@@ -33,6 +33,18 @@ pub(crate) enum BuiltInFunction {
     Sample,
     Not,
     Conjugate,
+    Arg,
+    Integrate,
+    Derivative,
+    Sum,
+    Product,
+    Pmt,
+    Pv,
+    Fv,
+    Compound,
+    Npv,
+    Irr,
+    MolarMass,
 }
 
 impl BuiltInFunction {
@@ -51,6 +63,17 @@ impl BuiltInFunction {
         )
     }
 
+    /// Returns the number of arguments this function takes. Functions
+    /// with more than one argument are curried, e.g. `integrate f a b`.
+    pub(crate) const fn arity(self) -> usize {
+        match self {
+            Self::Compound => 4,
+            Self::Integrate | Self::Sum | Self::Product | Self::Pmt | Self::Pv | Self::Fv => 3,
+            Self::Derivative | Self::Npv => 2,
+            _ => 1,
+        }
+    }
+
     pub(crate) fn invert(self) -> Result<Value, FendError> {
         Ok(match self {
             Self::Sin => Value::BuiltInFunction(Self::Asin),
@@ -92,6 +115,18 @@ impl BuiltInFunction {
             Self::Sample => "sample",
             Self::Not => "not",
             Self::Conjugate => "conjugate",
+            Self::Arg => "arg",
+            Self::Integrate => "integrate",
+            Self::Derivative => "derivative",
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Pmt => "pmt",
+            Self::Pv => "pv",
+            Self::Fv => "fv",
+            Self::Compound => "compound",
+            Self::Npv => "npv",
+            Self::Irr => "irr",
+            Self::MolarMass => "molar_mass",
         }
     }
 
@@ -118,6 +153,18 @@ impl BuiltInFunction {
             "sample" => Self::Sample,
             "not" => Self::Not,
             "conjugate" => Self::Conjugate,
+            "arg" => Self::Arg,
+            "integrate" => Self::Integrate,
+            "derivative" => Self::Derivative,
+            "sum" => Self::Sum,
+            "product" => Self::Product,
+            "pmt" => Self::Pmt,
+            "pv" => Self::Pv,
+            "fv" => Self::Fv,
+            "compound" => Self::Compound,
+            "npv" => Self::Npv,
+            "irr" => Self::Irr,
+            "molar_mass" => Self::MolarMass,
             _ => return Err(FendError::DeserializationError),
         })
     }
@@ -18,6 +18,7 @@ fn test_serialization_roundtrip(context: &mut Context) {
 #[track_caller]
 fn test_eval_simple(input: &str, expected: &str) {
     let mut context = Context::new();
+    context.set_exchange_rate_handler_v1(fend_core::test_utils::dummy_currency_handler);
     assert_eq!(
         evaluate(input, &mut context).unwrap().get_main_result(),
         expected
@@ -2106,7 +2107,7 @@ fn five_percent_plus_one() {
 
 #[test]
 fn point_one_plus_five_percent() {
-    test_eval("0.1 + 5%", "0.15");
+    test_eval("0.1 + 5%", "0.105");
 }
 
 #[test]
@@ -4528,6 +4529,19 @@ fn maths_with_earth_properties() {
     );
 }
 
+#[test]
+fn astronomical_bodies() {
+    test_eval("mass of mars", "641710000000000000000000 kg");
+    test_eval("mars.radius", "3389.5 km");
+    test_eval("gravity of jupiter", "24.79 m / s^2");
+    test_eval("earth.eccentricity", "0.0167086");
+    test_eval_simple(
+        "orbital_period of earth to years",
+        "approx. 1.0000387803 years",
+    );
+    expect_error("mars.colour", Some("could not find key in object"));
+}
+
 #[test]
 fn kelvin_to_rankine() {
     test_eval("273K to °R", "491.4 °R");
@@ -4540,12 +4554,12 @@ fn joule_per_kelvin_to_joule_per_celsius() {
 
 #[test]
 fn kelvin_plus_celsius() {
-    test_eval("1K+1°C", "2 K");
+    test_eval("1K+1°C", "2 °C");
 }
 
 #[test]
 fn kelvin_plus_fahrenheit() {
-    test_eval("1K+1°F", "approx. 1.5555555555 K");
+    test_eval("1K+1°F", "2.8 °F");
 }
 
 #[test]
@@ -4560,7 +4574,12 @@ fn fahrenheit_plus_kelvin() {
 
 #[test]
 fn celsius_plus_fahrenheit() {
-    test_eval("1°C+1°F", "approx. 1.5555555555 °C");
+    expect_error(
+        "1°C+1°F",
+        Some(
+            "cannot add two absolute temperatures, use a temperature difference like 5 Δ°C instead",
+        ),
+    );
 }
 
 #[test]
@@ -4570,7 +4589,36 @@ fn celsius_plus_rankine() {
 
 #[test]
 fn fahrenheit_plus_celsius() {
-    test_eval("1°F+1°C", "2.8 °F");
+    expect_error(
+        "1°F+1°C",
+        Some(
+            "cannot add two absolute temperatures, use a temperature difference like 5 Δ°C instead",
+        ),
+    );
+}
+
+#[test]
+fn temperature_differences() {
+    test_eval("20 °C - 15 °C", "5 Δ°C");
+    test_eval("20 °C - 59 °F", "5 Δ°C");
+    test_eval("70 °F - 20 °C", "3.6 Δ°F");
+    test_eval("20 millicelsius - 10 millicelsius", "0.01 Δ°C");
+    test_eval("5 Δ°C to Δ°F", "9 Δ°F");
+    test_eval("(20 °C - 15 °C) to K", "5 K");
+    test_eval("20 °C + 5 Δ°C", "25 °C");
+    test_eval("5 Δ°C + 20 °C", "25 °C");
+    test_eval("20 °C - 5 Δ°F", "approx. 17.2222222222 °C");
+    test_eval_simple("5 delta_celsius to delta_fahrenheit", "9 delta fahrenheit");
+    expect_error(
+        "20 °C + 5 °C",
+        Some(
+            "cannot add two absolute temperatures, use a temperature difference like 5 Δ°C instead",
+        ),
+    );
+    expect_error(
+        "5 Δ°C - 20 °C",
+        Some("cannot subtract an absolute temperature from a temperature difference"),
+    );
 }
 
 #[test]
@@ -5161,6 +5209,207 @@ fn custom_base_unit_in_calculation_2() {
     test_eval("5 'pigeons' per meter / 'pigeons'", "5 meters");
 }
 
+#[test]
+fn user_defined_unit() {
+    test_eval("unit smoot = 67 inches", "1 smoot");
+    test_eval("unit smoot = 67 inches; 3 smoot to m", "5.1054 m");
+    test_eval(
+        "unit furlong_per_fortnight = furlong / fortnight; 1 furlong_per_fortnight to mm/s",
+        "approx. 0.1663095238 mm / s",
+    );
+    test_eval(
+        "unit smoot = 67 inches; unit smoot = 2 m; 3 smoot to m",
+        "6 m",
+    );
+}
+
+#[test]
+fn user_defined_base_unit() {
+    test_eval("unit widget; 5 widget * 3", "15 widget");
+}
+
+#[test]
+fn user_defined_unit_prefixes() {
+    test_eval("unit l@smoot = 67 inches; 1 kilosmoot to km", "1.7018 km");
+    test_eval("unit s@Sm = 67 inches; 2 kSm to m", "3403.6 m");
+    test_eval("unit lp@duo = 2; 3 duometers to m", "6 m");
+    expect_error(
+        "unit smoot = 67 inches; 1 kilosmoot",
+        Some("unknown identifier 'kilosmoot'"),
+    );
+    expect_error(
+        "unit x@smoot = 67 inches",
+        Some("unknown prefix rule 'x@', expected one of l@, lp@, s@ or sp@"),
+    );
+}
+
+#[test]
+fn user_defined_unit_cannot_shadow_builtin() {
+    expect_error(
+        "unit m = 2 ft",
+        Some("cannot redefine the built-in unit 'm'"),
+    );
+}
+
+#[test]
+fn user_defined_unit_serialization() {
+    let mut context = Context::new();
+    evaluate("unit l@smoot = 67 inches", &mut context).unwrap();
+    let mut v = vec![];
+    context.serialize_variables(&mut v).unwrap();
+    let mut new_context = Context::new();
+    new_context
+        .deserialize_variables(&mut v.as_slice())
+        .unwrap();
+    assert_eq!(
+        evaluate("2 kilosmoot to km", &mut new_context)
+            .unwrap()
+            .get_main_result(),
+        "3.4036 km"
+    );
+}
+
+#[test]
+fn describe_units() {
+    test_eval_simple(
+        "describe newton",
+        "newton: force\ndefinition: kg m / s^2\nbase units: kilogram meter / second^2\naliases: newtons, N",
+    );
+    test_eval_simple(
+        "help kelvin",
+        "kelvin: SI base unit of temperature\ndefinition: base unit\nbase units: kelvin\naliases: K",
+    );
+    test_eval_simple(
+        "describe c",
+        "c: speed of light in vacuum (exact)\ndefinition: 299792458 m/s\nbase units: meter / second\naliases: light\nuncertainty: exact",
+    );
+    test_eval_simple(
+        "unit smoot = 67 inches; describe smoot",
+        "smoot: user-defined unit\nbase units: meter",
+    );
+    expect_error("describe foo", Some("unknown identifier 'foo'"));
+}
+
+#[test]
+fn describe_unit_api() {
+    let mut context = Context::new();
+    let description = fend_core::describe_unit("N", &mut context).unwrap();
+    assert_eq!(description.name(), "newton");
+    assert_eq!(description.description(), "force");
+    assert_eq!(description.definition(), "kg m / s^2");
+    assert_eq!(description.base_units(), "kilogram meter / second^2");
+    assert_eq!(description.aliases(), ["newtons", "N"]);
+    let description = fend_core::describe_unit("EUR", &mut context).unwrap();
+    assert_eq!(description.description(), "currency");
+    assert_eq!(description.base_units(), "");
+    assert_eq!(description.aliases(), ["euro", "\u{20ac}"]);
+    assert_eq!(description.uncertainty(), "");
+    let description = fend_core::describe_unit("m_e", &mut context).unwrap();
+    assert_eq!(description.name(), "electron_mass");
+    assert_eq!(description.uncertainty(), "2.8e-40 kg");
+    let description = fend_core::describe_unit("planck", &mut context).unwrap();
+    assert_eq!(description.uncertainty(), "exact");
+}
+
+#[test]
+fn codata_constants() {
+    test_eval(
+        "electron_mass",
+        "0.00000000000000000000000000000091093837015 kg",
+    );
+    test_eval_simple("m_p / m_e", "approx. 1836.15267344");
+    test_eval("molar_gas_constant", "8.31446261815324 J mol^-1 K^-1");
+    test_eval("faraday_constant", "96485.3321233100184 coulomb / mol");
+    test_eval_simple("1/alpha", "approx. 137.0359990836");
+    test_eval_simple("E_h to eV", "approx. 27.2113862459 eV");
+    test_eval_simple("R_K", "approx. 25812.8074593045 ohms");
+    test_eval_simple("G_0", "approx. 0.0000774809 S");
+    test_eval_simple("K_J", "approx. 483597848416983.6324476582 Hz / V");
+    test_eval_simple("Phi_0 to fWb", "approx. 2.0678338484 fWb");
+    test_eval_simple(
+        "describe a_0",
+        "bohr_radius: Bohr radius\ndefinition: 5.29177210903e-11 m\nbase units: meter\naliases: a_0\nuncertainty: 8e-21 m",
+    );
+}
+
+#[track_caller]
+fn test_eval_with_unit_definitions(definitions: &str, input: &str, expected: &str) {
+    let mut context = Context::new();
+    context.load_unit_definitions(definitions).unwrap();
+    assert_eq!(
+        evaluate(input, &mut context).unwrap().get_main_result(),
+        expected
+    );
+}
+
+const UNIT_DEFINITIONS: &str = "
+# oilfield units
+oilbarrel    42 gallons    # US barrel
+mcf          1000 ft^3
+third        1|3
+myriad-      10000
+widget       !
+longunit     2 \\
+             meters
+m            3 ft          # already built in
+!locale en_US
+";
+
+#[test]
+fn load_unit_definitions() {
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "1 oilbarrel to L", "158.987294928 L");
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "1 mcf to m^3", "28.316846592 m^3");
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "6 third to 1", "2");
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "3 widget * 2", "6 widget");
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "1 longunit to cm", "200 cm");
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "1 m to mm", "1000 mm");
+}
+
+#[test]
+fn load_unit_definitions_with_prefixes() {
+    test_eval_with_unit_definitions(UNIT_DEFINITIONS, "1 myriadmeter to km", "10 km");
+    test_eval_with_unit_definitions(
+        UNIT_DEFINITIONS,
+        "2 kilooilbarrel to m^3",
+        "317.974589856 m^3",
+    );
+}
+
+#[test]
+fn skip_unsupported_unit_definitions() {
+    let definitions = "
+onebar       1 bar
+tempF(x)     units=[1;K] x
+zippers[in]  1 0.5 2 1
+twobar       2 bar
+";
+    test_eval_with_unit_definitions(definitions, "3 onebar to bar", "3 bar");
+    test_eval_with_unit_definitions(definitions, "3 twobar to bar", "6 bar");
+    let mut context = Context::new();
+    context.load_unit_definitions(definitions).unwrap();
+    assert!(evaluate("1 zippers", &mut context).is_err());
+}
+
+#[test]
+fn load_invalid_unit_definitions() {
+    let mut context = Context::new();
+    assert_eq!(
+        context.load_unit_definitions("a 1 m\nlonely"),
+        Err("invalid unit definition on line 2: unsupported unit definition 'lonely'".to_string())
+    );
+    assert_eq!(
+        context.load_unit_definitions("bee 2 foo"),
+        Err("invalid unit definition on line 1: unknown identifier 'foo'".to_string())
+    );
+    // units defined before the error are kept
+    assert_eq!(
+        evaluate("2 a to cm", &mut context)
+            .unwrap()
+            .get_main_result(),
+        "200 cm"
+    );
+}
+
 #[test]
 fn five_k() {
     test_eval("5k", "5000");
@@ -5337,6 +5586,41 @@ fn gbp_symbol() {
     test_eval("£5 + £3", "£8");
 }
 
+#[test]
+fn currency_minor_units() {
+    test_eval_simple("10 USD / 3", "approx. 3.33 USD");
+    test_eval_simple("1000 JPY / 3", "approx. 333 JPY");
+    test_eval_simple("10 KWD / 3", "approx. 3.333 KWD");
+    test_eval_simple("$4.999", "approx. $5.00");
+    test_eval_simple("$0.125", "approx. $0.13");
+    test_eval("$3.5", "$3.50");
+    test_eval("5 dollars / 4", "1.25 dollars");
+    test_eval_simple("10 USD / 3 to fraction", "10/3 USD");
+}
+
+#[test]
+fn currency_symbols() {
+    test_eval_simple("$10 / 3", "approx. $3.33");
+    test_eval_simple("€10 / 3", "approx. 3,33 €");
+    test_eval_simple("€5 + €3.5", "8,50 €");
+    test_eval_simple("¥1000 / 3", "approx. ¥333");
+    test_eval("5 EUR", "5 EUR");
+}
+
+#[test]
+fn currency_bankers_rounding() {
+    let mut ctx = Context::new();
+    ctx.use_bankers_rounding();
+    assert_eq!(
+        evaluate("$0.125", &mut ctx).unwrap().get_main_result(),
+        "approx. $0.12"
+    );
+    assert_eq!(
+        evaluate("$0.135", &mut ctx).unwrap().get_main_result(),
+        "approx. $0.14"
+    );
+}
+
 #[test]
 fn two_statements() {
     test_eval("2; 4", "4");
@@ -5372,6 +5656,375 @@ fn farad_conversion() {
     test_eval("1 farad to A^2 kg^-1 m^-2 s^4", "1 A^2 s^4 kg^-1 m^-2");
 }
 
+#[test]
+fn products_of_units_are_not_renamed() {
+    test_eval("1 kg m / s^2", "1 kg m / s^2");
+    test_eval("5 N m", "5 N m");
+    test_eval("5 N * 2 m", "10 N m");
+    test_eval("5 V * 2 A", "10 V A");
+}
+
+#[test]
+fn as_simplest() {
+    test_eval("1 kg m / s^2 as simplest", "1 newton");
+    test_eval("10 J/s as simplest", "10 watts");
+    test_eval("5 V * 2 A as simplest", "10 watts");
+    test_eval("3 A * 2 s as simplest", "6 coulomb");
+    test_eval("2 kg m^2 / s^3 / A^2 as simplest", "2 ohms");
+    test_eval("1 A^2 s^4 kg^-1 m^-2 as simplest", "1 farad");
+    test_eval("2 kW * 3 h as simplest", "21600000 joules");
+    test_eval("1 g cm / s^2 as simplest", "0.00001 newtons");
+    test_eval("(1 kg m / s^2 to kg m / s^2) as simplest", "1 newton");
+    test_eval("5 N as simplest", "5 N");
+    test_eval("1 m / s as simplest", "1 m / s");
+}
+
+#[test]
+fn logarithmic_unit_conversions() {
+    test_eval("30 dBm to W", "1 W");
+    test_eval("-10 dBm to mW", "0.1 mW");
+    test_eval("30.5 dBm to mW", "approx. 1122.0184543019 mW");
+    test_eval("1 W to dBm", "approx. 30 dBm");
+    test_eval("2 mW to dBm", "approx. 3.0102999566 dBm");
+    test_eval("0 dBW to dBm", "approx. 30 dBm");
+    test_eval("1 V to dBµV", "approx. 120 dBµV");
+    test_eval("120 dBV to V", "1000000 V");
+    test_eval("94 dB_SPL to Pa", "approx. 1.0023744672 Pa");
+    test_eval("1 Pa to dBSPL", "approx. 93.9794000867 dBSPL");
+    test_eval("30 dBm to dBm", "30 dBm");
+    expect_error(
+        "30 dBm to V",
+        Some("cannot convert from kilogram meter^2 / second^3 to V: units 'kilogram meter^2 / second^3' and 'kilogram meter^2 ampere^-1 second^-3' are incompatible"),
+    );
+}
+
+#[test]
+fn logarithmic_unit_arithmetic() {
+    test_eval("30 dBm + 30 dBm", "approx. 33.0102999566 dBm");
+    test_eval("0 dBm + 0 dBW", "approx. 30.0043407747 dBm");
+    test_eval("30 dBm + 1 W", "approx. 33.0102999566 dBm");
+    test_eval("30 dBm + 10 dB", "approx. 40 dBm");
+    test_eval("5 dB + 30 dBm", "approx. 35 dBm");
+    test_eval("30 dBm - 10 dB", "approx. 20 dBm");
+    test_eval_simple("33 dBm - 30 dBm", "3 dB");
+    test_eval("0 dBW - 0 dBm", "approx. 30 dB");
+    test_eval_simple("30 dBV - 24 dBV", "6 dB");
+    test_eval("30 dBm + 10 dB - 10 dB", "approx. 30 dBm");
+    test_eval("1 W + 30 dBm", "2 W");
+    test_eval("2 * 30 dBm", "approx. 33.0102999566 dBm");
+    test_eval("30 dBm * 2", "approx. 33.0102999566 dBm");
+    test_eval("30 dBm / 2", "approx. 26.9897000433 dBm");
+    test_eval("-30 dBm", "-30 dBm");
+    test_eval("10 dB to neper", "approx. 1.1512925464 nepers");
+}
+
+#[test]
+fn relative_decibels_are_additive() {
+    test_eval("3 dB + 3 dB", "approx. 6 dB");
+    test_eval("(10 dB + 10 dB) - 10 dB", "approx. 10 dB");
+    test_eval("1 Np + 1 Np", "2 Np");
+    test_eval("1 B + 1 B", "2 B");
+    test_eval("2 * 3 dB", "approx. 6 dB");
+}
+
+#[test]
+fn reciprocal_unit_conversions() {
+    test_eval_simple(
+        "30 mpg to L/100km",
+        "approx. 7.8404861111 L / 100 km (converted via reciprocal)",
+    );
+    test_eval_simple(
+        "30 mpg to L/(100 km)",
+        "approx. 7.8404861111 L / 100 km (converted via reciprocal)",
+    );
+    test_eval_simple(
+        "8 L/(2 km) to mpg",
+        "approx. 0.5880364583 mpg (converted via reciprocal)",
+    );
+    test_eval_simple("50 Hz to s", "0.02 s (converted via reciprocal)");
+    test_eval_simple("0.02 s to Hz", "50 Hz (converted via reciprocal)");
+    test_eval_simple("2 ohm to S", "0.5 S (converted via reciprocal)");
+    test_eval_simple("4 S to ohms", "0.25 ohms (converted via reciprocal)");
+    test_eval_simple("0.1 L/km to L/100km", "10 L / 100 km");
+    test_eval_simple("(50 Hz to s) * 2", "0.04 s");
+    test_eval_simple("a = 50 Hz to s; a * 2", "0.04 s");
+    expect_error(
+        "1 m to s",
+        Some("cannot convert from m to s: units 'meter' and 'second' are incompatible"),
+    );
+    // reciprocals are only used for explicit conversions
+    expect_error(
+        "1 s + 2 Hz",
+        Some("cannot convert from Hz to s: units 'second^-1' and 'second' are incompatible"),
+    );
+    expect_error("integrate (x: x) (1 s) (2 Hz)", None);
+}
+
+#[test]
+fn cgs_and_engineering_units() {
+    test_eval("1 erg to J", "0.0000001 J");
+    test_eval("1 dyne to N", "0.00001 N");
+    test_eval("1 poise to Pa s", "0.1 Pa s");
+    test_eval("1 stokes to m^2/s", "0.0001 m^2 / s");
+    test_eval("1 tesla to gauss", "10000 gauss");
+    test_eval("1 Mx to Wb", "0.00000001 Wb");
+    test_eval("760 torr to atm", "1 atm");
+    test_eval_simple("1 torr to Pa", "approx. 133.322368421 Pa");
+    test_eval("2 mAh to coulomb", "7.2 coulomb");
+    test_eval("9600 baud to Hz", "9600 Hz");
+    test_eval_simple("1 fc to lux", "approx. 10.7639104167 lux");
+    test_eval("1 barye to Pa", "0.1 Pa");
+    test_eval("1 Gal to m/s^2", "0.01 m / s^2");
+    test_eval("5 mGal to m/s^2", "0.00005 m / s^2");
+    test_eval("1 ksi to psi", "1000 psi");
+    test_eval("1 at to kPa", "98.0665 kPa");
+    expect_error(
+        "1920 px to cm at 0 ppi",
+        Some("pixel density must be positive"),
+    );
+    test_eval("1 inH2O to Pa", "249.08891 Pa");
+    test_eval("1 VA to W", "1 W");
+    test_eval("1 kgf to N", "9.80665 N");
+}
+
+#[test]
+fn imperial_and_kitchen_volumes() {
+    test_eval("1 imperial_pint to mL", "568.26125 mL");
+    test_eval_simple(
+        "1 uk_pint to imperial_fluid_ounces",
+        "20 imperial fluid ounces",
+    );
+    test_eval_simple("1 us_pint to us_fluid_ounces", "16 us fluid ounces");
+    test_eval("1 cup to mL", "236.5882365 mL");
+    test_eval("1 cup to tbsp", "16 tbsp");
+    test_eval("1 tablespoon to teaspoons", "3 teaspoons");
+    test_eval("1 metric_cup to mL", "250 mL");
+}
+
+#[test]
+fn screen_units() {
+    test_eval("12 pt to px", "16 px");
+    test_eval("1 pica to pt", "12 pt");
+    test_eval("2 em to px", "32 px");
+    test_eval("1.5 rem to pt", "18 pt");
+    test_eval("1 dip to px", "0.6 px");
+    test_eval("1 pint to mL", "473.176473 mL");
+    // `pt` and `rem` used to mean pints and the radiation unit
+    test_eval("1 gallon to pint", "8 pints");
+    test_eval("1 roentgen_equivalent_man to mSv", "10 mSv");
+    test_eval("100 roentgen_equivalent_man to Sv", "1 Sv");
+    test_eval_simple("1920 px to cm at 96 dpi", "50.8 cm");
+    test_eval_simple("1920 px to cm at 160", "30.48 cm");
+    test_eval_simple("1920 px to cm at 326 ppi", "approx. 14.9595092024 cm");
+    test_eval_simple("1920 px at 160 to cm", "30.48 cm");
+    test_eval_simple("10 px at 96 dpi", "10 px");
+    // without a number, `at` is the technical atmosphere
+    test_eval("2 at + 1 at", "3 at");
+    test_eval("1 at to kPa", "98.0665 kPa");
+    expect_error(
+        "1920 px to cm at 0 ppi",
+        Some("pixel density must be positive"),
+    );
+    expect_error(
+        "1 px to cm at 96 kg",
+        Some("cannot convert from kg to dpi: units 'kilogram' and 'meter^-1' are incompatible"),
+    );
+}
+
+#[test]
+fn screen_units_with_context() {
+    let mut ctx = Context::new();
+    ctx.set_pixels_per_inch(160).unwrap();
+    ctx.set_base_font_size(20).unwrap();
+    assert_eq!(
+        evaluate("1 inch to px", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "160 px"
+    );
+    assert_eq!(
+        evaluate("1 em to px", &mut ctx).unwrap().get_main_result(),
+        "20 px"
+    );
+    assert_eq!(
+        ctx.set_pixels_per_inch(0),
+        Err("pixel density must be positive".to_string())
+    );
+    assert_eq!(
+        ctx.set_base_font_size(0),
+        Err("base font size must be positive".to_string())
+    );
+    // the previous settings are kept
+    assert_eq!(
+        evaluate("1 inch to px", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "160 px"
+    );
+}
+
+#[test]
+fn auto_prefix() {
+    test_eval("1500000000 Hz as auto_prefix", "1.5 GHz");
+    test_eval("0.000000047 farad to auto_prefix", "47 nanofarad");
+    test_eval("0.000047 m as auto_prefix", "47 \u{b5}m");
+    test_eval("1500 m/s as auto_prefix", "1.5 km / s");
+    test_eval("1000000 m^2 as auto_prefix", "1 km^2");
+    test_eval("1000 mm as auto_prefix", "1 m");
+    test_eval("0.5 kg as auto_prefix", "500 g");
+    test_eval("5000 meters as auto_prefix", "5 kilometers");
+    test_eval("5000 KiB as auto_prefix", "4.8828125 MiB");
+    test_eval("5000 feet as auto_prefix", "5000 feet");
+    test_eval("100 USD as auto_prefix", "100 USD");
+    test_eval("20 \u{b0}C as auto_prefix", "20 \u{b0}C");
+    test_eval("5 as auto_prefix", "5");
+    // `F` means degrees Fahrenheit unless farads are enabled, and
+    // temperatures don't take prefixes
+    test_eval("0.000000047 F as auto_prefix", "0.000000047 \u{b0}F");
+    // values outside the range of prefixes keep the unprefixed unit
+    test_eval("1e30 m as auto_prefix", "1000000000000000000000000000000 m");
+    test_eval("0.5 ym as auto_prefix", "0.0000000000000000000000005 m");
+    test_eval("999 Ym as auto_prefix", "999 Ym");
+}
+
+#[test]
+fn auto_prefix_by_default() {
+    let mut ctx = Context::new();
+    ctx.use_coulomb_and_farad();
+    ctx.use_auto_prefix();
+    assert_eq!(
+        evaluate("0.000000047 F", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "47 nF"
+    );
+    assert_eq!(
+        evaluate("0.000000047 F to mF", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "0.000047 mF"
+    );
+    assert_eq!(
+        evaluate("0.000000047 F as auto_prefix", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "47 nF"
+    );
+}
+
+#[test]
+fn preferred_unit_system() {
+    let eval = |input: &str, ctx: &mut Context| {
+        evaluate(input, ctx).unwrap().get_main_result().to_string()
+    };
+    let mut ctx = Context::new();
+    assert_eq!(eval("60 mph * 2 h", &mut ctx), "120 mph h");
+    ctx.use_metric_units();
+    assert_eq!(eval("60 mph * 2 h", &mut ctx), "193.12128 km");
+    assert_eq!(eval("0.1 mph * 2 s", &mut ctx), "8.9408 cm");
+    assert_eq!(eval("5 lb * 2 ft / s^2", &mut ctx), "1.38254954376 N");
+    assert_eq!(eval("60 mph * 2 h to mi", &mut ctx), "120 mi");
+    assert_eq!(eval("5 miles", &mut ctx), "5 miles");
+    assert_eq!(eval("3 kg * 2 USD", &mut ctx), "6 kg USD");
+    ctx.use_imperial_units();
+    assert_eq!(
+        eval("100 km / (2 h)", &mut ctx),
+        "approx. 31.0685596118 mph"
+    );
+    assert_eq!(eval("0.1 mph * 2 s", &mut ctx), "3.52 inches");
+    ctx.use_si_base_units();
+    assert_eq!(eval("60 mph * 2 h", &mut ctx), "193121.28 meters");
+    assert_eq!(eval("1 N m", &mut ctx), "1 kilogram meter^2 / second^2");
+}
+
+#[test]
+fn case_insensitive_units() {
+    test_eval("1 TORR", "1 torr");
+    test_eval_simple("1 KHZ", "1 kHz");
+    test_eval_simple("1 zj", "1 zJ");
+    expect_error(
+        "1 mhz",
+        Some("ambiguous unit 'mhz', which could refer to 'mHz' (milli) or 'MHz' (mega)"),
+    );
+    expect_error(
+        "1 MI",
+        Some("ambiguous unit 'MI', which could refer to 'Mi' (2^20) or 'mi' (mile)"),
+    );
+    let mut ctx = Context::new();
+    let result = evaluate("2 HZ", &mut ctx).unwrap();
+    assert_eq!(result.get_main_result(), "2 Hz");
+    assert_eq!(
+        result.get_warnings().collect::<Vec<_>>(),
+        ["'HZ' was interpreted as 'Hz', which has a different capitalization"]
+    );
+    let result = evaluate("2 Hz", &mut ctx).unwrap();
+    assert_eq!(result.get_warnings().count(), 0);
+}
+
+#[test]
+fn substance_densities() {
+    test_eval_simple("1 cup flour to grams", "approx. 125 grams");
+    test_eval_simple("2 cups of sugar to g", "approx. 400 g");
+    test_eval_simple("1 L gasoline to kg", "approx. 0.745 kg");
+    test_eval_simple("200 g flour to cups", "approx. 1.6 cups");
+    test_eval_simple("1 m^3 air to kg", "approx. 1.204 kg");
+    test_eval_simple("density of steel", "approx. 7.85 g / cm^3");
+    test_eval_simple("steel.density", "approx. 7.85 g / cm^3");
+    // planets and moons also have a density, but they aren't substances
+    expect_error("1 L earth", None);
+    expect_error("2 earth", None);
+    expect_error("1 kg of moon", None);
+    expect_error(
+        "1 m flour",
+        Some("a substance can only be combined with a mass or a volume, e.g. `1 cup flour`"),
+    );
+}
+
+#[test]
+fn molar_masses() {
+    test_eval("molar_mass(\"H2SO4\")", "98.07848 g / mol");
+    test_eval("molar_mass \"Ca(OH)2\"", "74.09268 g / mol");
+    test_eval("molar_mass \"K4[Fe(CN)6]\"", "368.3426 g / mol");
+    test_eval("molar_mass \"CuSO4\u{b7}5H2O\"", "249.685 g / mol");
+    test_eval("molar_mass \"iron\"", "55.845 g / mol");
+    test_eval("2 mol of \"NaCl\" to g", "116.88553856 g");
+    test_eval_simple("100 g of \"H2O\" to mol", "approx. 5.5508435061 mol");
+    expect_error("molar_mass \"Xx\"", Some("unknown element 'Xx'"));
+    expect_error(
+        "molar_mass \"H2(O\"",
+        Some("invalid chemical formula 'H2(O'"),
+    );
+    expect_error("molar_mass 5", Some("expected a string"));
+}
+
+#[test]
+fn compound_unit_conversion() {
+    test_eval_simple("5.75 ft to ft and in", "5 ft 9 inches");
+    test_eval_simple("5.75 ft to [ft, inches]", "5 ft 9 inches");
+    test_eval_simple(
+        "100000 s to days, hours, minutes, seconds",
+        "1 day 3 hours 46 minutes 40 seconds",
+    );
+    test_eval_simple(
+        "100000 s to days, hours, minutes and seconds",
+        "1 day 3 hours 46 minutes 40 seconds",
+    );
+    test_eval_simple("200 lb to st, lb and oz", "14 st 4 lbs");
+    test_eval_simple("3.3 lb to lb, oz", "3 lbs 4.8 oz");
+    test_eval_simple("-5.75 ft to ft, in", "-5 ft 9 inches");
+    test_eval_simple("-0.5 ft to ft, in", "-6 inches");
+    test_eval_simple("pi m to ft, in", "approx. 10 ft 3.6847501413 inches");
+    test_eval("(5.75 ft to ft and in) to m", "1.7526 m");
+    test_eval("(5.75 ft to ft, in) + 1 ft", "6.75 ft");
+    test_eval_simple("2 hours to hours, minutes", "2 hours");
+    test_eval_simple("0 s to h, min", "0 mins");
+    test_eval_simple("5 to hex and 3", "1");
+    expect_error(
+        "1 kg to ft, in",
+        Some("cannot convert from kg to ft: units 'kilogram' and 'meter' are incompatible"),
+    );
+}
+
 #[test]
 fn coulomb_farad_mode() {
     let mut ctx = Context::new();
@@ -5644,3 +6297,418 @@ fn rshift() {
 fn shift_and_and() {
     test_eval("54 << 1 & 54 >> 1", "8");
 }
+
+#[test]
+fn solve_linear() {
+    test_eval("solve x: 2x - 3", "1.5");
+    test_eval("solve x: 3x + 4 = 10", "2");
+}
+
+#[test]
+fn solve_quadratic() {
+    test_eval_simple("solve x: x^2 + 3x = 10", "{\n    x_1: -5,\n    x_2: 2\n}");
+    test_eval("x_2 of (solve x: x^2 + 3x = 10)", "2");
+    test_eval_simple(
+        "solve x: x^2 = 2",
+        "{\n    x_1: approx. -1.4142135623,\n    x_2: approx. 1.4142135623\n}",
+    );
+    test_eval_simple(
+        "solve x: x^2 - 3.1x + 2.4",
+        "{\n    x_1: 1.5,\n    x_2: 1.6\n}",
+    );
+    test_eval("solve x: (x-3)^2", "3");
+    test_eval("solve x: x = 1e8", "100000000");
+}
+
+#[test]
+fn solve_numerically() {
+    test_eval("solve x: x^3 = 8", "2");
+    test_eval("solve x: cos x = x", "approx. 0.7390851332");
+    test_eval_simple(
+        "solve x: (x - pi)^2 * (x + 10)^2",
+        "{\n    x_1: -10,\n    x_2: approx. 3.1415926535\n}",
+    );
+    test_eval_simple(
+        "solve x: x^3 - 3.1x^2 + 2.4x",
+        "{\n    x_1: 0,\n    x_2: 1.5,\n    x_3: 1.6\n}",
+    );
+}
+
+#[test]
+fn solve_exponential_equations() {
+    test_eval("solve x: 2^x = 8", "3");
+    test_eval("solve x: e^x = 5", "approx. 1.6094379124");
+    test_eval("solve x: 10^x = 1e15", "15");
+    test_eval("solve x: 2^x = 0.001", "approx. -9.9657842846");
+    test_eval("solve x: x e^x = 1", "approx. 0.5671432904");
+}
+
+#[test]
+fn large_powers_of_approximate_numbers() {
+    test_eval("e^100 / 10^43", "approx. 2.6881171418");
+    test_eval("2^0.000003", "approx. 1.0000020794");
+    test_eval("(2^-1000 * e^0) * 2^1000", "approx. 1");
+}
+
+#[test]
+fn solve_with_too_many_solutions() {
+    expect_error(
+        "solve x: sin x = 0.5",
+        Some("the equation has too many solutions"),
+    );
+}
+
+#[test]
+fn solve_with_units() {
+    test_eval_simple("solve x: x^2 = 4 m^2", "{\n    x_1: -2 m,\n    x_2: 2 m\n}");
+    test_eval("solve d: d / (2 s) = 3 m/s", "6 m");
+    test_eval_simple(
+        "solve t: 100 m = 0.5 * 9.81 m/s^2 * t^2",
+        "{\n    t_1: approx. -4.5152364098 s,\n    t_2: approx. 4.5152364098 s\n}",
+    );
+}
+
+#[test]
+fn solve_result_in_variable() {
+    test_eval("a = solve y: y^3 = 27; a * 2", "6");
+}
+
+#[test]
+fn solve_without_solution() {
+    expect_error("solve x: x^2 + 1", Some("unable to find a real solution"));
+    expect_error("solve x: 1/x", Some("unable to find a real solution"));
+}
+
+#[test]
+fn integrate_polynomial() {
+    test_eval("integrate (x: x^2) 0 1", "approx. 0.3333333333");
+    test_eval("integrate (x: x) 1 0", "approx. -0.5");
+    test_eval("integrate sin 0 pi", "approx. 2");
+}
+
+#[test]
+fn integrate_with_units() {
+    test_eval("integrate (t: 5 W) (0 h) (2 h) to Wh", "approx. 10 Wh");
+    test_eval(
+        "integrate (x: x^2) (0 m) (100 cm)",
+        "approx. 0.3333333333 m^3",
+    );
+}
+
+#[test]
+fn integrate_partial_application() {
+    test_eval_simple("integrate (x: x^2) 0", "integrate (\\x.(x^2)) (0)");
+    test_eval("f = integrate (x: 2x) 0; f 3", "approx. 9");
+}
+
+#[test]
+fn integrate_and_differentiate_with_call_syntax() {
+    test_eval("integrate(x: x^2, 0, 1)", "approx. 0.3333333333");
+    test_eval("f = x: x^2; integrate(f, 0, 1)", "approx. 0.3333333333");
+    test_eval("integrate(sin, 0, pi)", "approx. 2");
+    test_eval("derivative(x: x^2, 3)", "approx. 6");
+    test_eval("f = x: x^2; derivative(f, 3)", "approx. 6");
+    test_eval("sin(pi / 2)", "1");
+    test_eval("(1 + 2)^2", "9");
+    expect_error("x = 5; x(1, 2)", Some("'5' is not a function"));
+    // commas in other parentheses are digit separators
+    test_eval("2 (1,000)", "2000");
+}
+
+#[test]
+fn integrate_errors() {
+    expect_error(
+        "integrate (x: \"a\") 0 1",
+        Some("function must return a number (found string)"),
+    );
+    expect_error("integrate (x: 1/x) 0 1", Some("integral does not converge"));
+}
+
+#[test]
+fn derivative_of_lambda() {
+    test_eval("derivative (x: x^2) 3", "approx. 6");
+    test_eval("derivative sin 0", "approx. 1");
+    test_eval(
+        "derivative (t: 4.9 m/s^2 * t^2) (2 s)",
+        "approx. 19.6 m / s",
+    );
+    expect_error(
+        "derivative (x: 1/x) 0",
+        Some("derivative does not converge"),
+    );
+    test_eval("derivative (x: abs x) 3", "approx. 1");
+    test_eval("derivative cos 0", "approx. 0");
+    test_eval("derivative (x: x^4) 0", "approx. 0");
+    expect_error(
+        "derivative(\\x. abs x, 0)",
+        Some("the function is not differentiable here"),
+    );
+    expect_error(
+        "derivative (x: abs x + x^2) 0",
+        Some("the function is not differentiable here"),
+    );
+}
+
+#[test]
+fn sum_over_range() {
+    test_eval("sum (k: k^2) 1 100", "338350");
+    test_eval("sum (k: 1/2^k) 1 10", "0.9990234375");
+    test_eval_simple("(sum (k: 1/k) 1 10) to fraction", "7381/2520");
+    test_eval("sum (k: k) (-3) 3", "0");
+    test_eval("sum (k: k) 5 1", "0");
+}
+
+#[test]
+fn sum_with_units() {
+    test_eval("sum (k: k m) 1 4", "10 m");
+}
+
+#[test]
+fn product_over_range() {
+    test_eval("product (k: k) 1 20", "2432902008176640000");
+    test_eval("n = 5; product (k: 2k) 1 n", "3840");
+    test_eval("product (k: k) 5 4", "1");
+}
+
+#[test]
+fn sum_and_product_with_call_syntax() {
+    test_eval("f = k: k; sum(f, 1, 100)", "5050");
+    test_eval("sum(k: k^2, 1, 100)", "338350");
+    test_eval("n = 5; product(k: 2k, 1, n)", "3840");
+}
+
+#[test]
+fn sum_non_integer_bounds() {
+    expect_error(
+        "sum (k: k) 1.5 3",
+        Some("cannot convert fraction to integer"),
+    );
+}
+
+#[test]
+fn list_literals() {
+    test_eval("[1, 2, 3]", "[1, 2, 3]");
+    test_eval("[]", "[]");
+    test_eval("[1,2]", "[1, 2]");
+    test_eval("[1,000, 2 + 3, 4 m]", "[1, 0, 5, 4 m]");
+    test_eval("1,000 + 2", "1002");
+    test_eval("(1,000) + 2", "1002");
+    test_eval("[(1,000), 2]", "[1000, 2]");
+    test_eval("flows = [-100, 60, 60]; flows", "[-100, 60, 60]");
+    expect_error("[1, 2] + 1", Some("expected a number"));
+}
+
+#[test]
+fn loan_payments() {
+    test_eval(
+        "pmt (6%/year) (360 months) (300000 USD)",
+        "approx. 1798.65 USD",
+    );
+    test_eval("pmt (5%) 10 (1000 USD)", "approx. 129.50 USD");
+    test_eval("pmt 0 (10 months) (100 USD)", "10 USD");
+    test_eval("pmt(5%, 10, 1000 USD)", "approx. 129.50 USD");
+    test_eval(
+        "rate = 6%/year; periods = 360 months; principal = 300000 USD; pmt(rate, periods, principal)",
+        "approx. 1798.65 USD",
+    );
+}
+
+#[test]
+fn present_and_future_value() {
+    test_eval(
+        "pv (6%/year) (360 months) (1798.65 USD)",
+        "approx. 299999.74 USD",
+    );
+    test_eval("fv (5%/year) (10 years) (100 USD)", "approx. 1257.79 USD");
+    test_eval("fv (0%/year) (10 years) (100 USD)", "1000 USD");
+    test_eval("pv(5%, 10, 100 USD)", "approx. 772.17 USD");
+}
+
+#[test]
+fn pv_and_fv_are_still_units() {
+    test_eval_simple("1 pv", "1 pV");
+    test_eval("1 pV to V", "0.000000000001 V");
+    test_eval("3 fV", "3 fV");
+    test_eval("pv = 2; pv * 3", "6");
+}
+
+#[test]
+fn compound_interest() {
+    test_eval(
+        "compound (1000 USD) (5%/year) (12/year) (10 years)",
+        "approx. 1647.01 USD",
+    );
+    test_eval(
+        "compound (1000 USD) (5%/year) (1/year) (2 years)",
+        "1102.50 USD",
+    );
+}
+
+#[test]
+fn net_present_value() {
+    test_eval("npv (10%) [-100, 60, 60]", "approx. 4.1322314049");
+    test_eval(
+        "npv (5%) [-1000 USD, 300 USD, 400 USD, 500 USD]",
+        "approx. 80.44 USD",
+    );
+    test_eval("npv 0 [-100, 60, 60]", "20");
+    test_eval("npv(10%/year, [-100, 60, 60])", "approx. 4.1322314049");
+    test_eval("npv(1%/month, [-100, 60, 60])", "approx. 18.2237035584");
+    test_eval("npv(10%, [-100, 60, 60])", "approx. 4.1322314049");
+    test_eval(
+        "rate = 10%; cashflows = [-100, 60, 60]; npv(rate, cashflows)",
+        "approx. 4.1322314049",
+    );
+}
+
+#[test]
+fn internal_rate_of_return() {
+    test_eval("irr [-100, 60, 60]", "approx. 13.06623863%");
+    test_eval("irr [-100,60,60]", "approx. 13.06623863%");
+    test_eval(
+        "irr [-1000 USD, 300 USD, 400 USD, 500 USD]",
+        "approx. 8.896339469%",
+    );
+    expect_error("irr [100, 100]", Some("unable to find a real solution"));
+    expect_error(
+        "irr []",
+        Some("expected a non-empty list, e.g. [-100, 60, 60]"),
+    );
+}
+
+#[test]
+fn modulo_with_large_powers() {
+    test_eval("(3^100 + 7) mod 13", "10");
+    test_eval("3^1000000 mod 7", "4");
+    test_eval("(2^64 * 3^40 - 1) mod 1000", "415");
+    test_eval("(2.5 * 2) mod 3", "2");
+    test_eval("(8 - 10) mod 5", "3");
+    test_eval("-7 mod 3", "2");
+    test_eval("(10 / 2) mod 3", "2");
+    test_eval("(2^3 + 0.5) * 2 mod 5", "2");
+    test_eval("0b1001010 mod 5", "0b100");
+    expect_error(
+        "7.5 mod 2",
+        Some("modulo is only supported for positive integers"),
+    );
+}
+
+#[test]
+fn modulo_evaluates_operands_once() {
+    test_eval("a = 5; (a = a + 1) mod 4; a", "6");
+    test_eval("a = 5; (2^(a = a + 1)) mod 7; a", "6");
+}
+
+#[test]
+fn modulo_evaluates_lhs_first() {
+    test_eval("a = 5; (a + 1) mod (a = 4)", "2");
+    test_eval("a = 5; (a^2) mod (a = 4)", "1");
+    test_eval("a = 5; a (mod (a = 4))", "1 (mod 4)");
+    expect_error("(1/0) mod foo", Some("division by zero"));
+    expect_error("foo mod (1/0)", Some("unknown identifier 'foo'"));
+    expect_error("(2^100000 / 0) mod foo", Some("division by zero"));
+}
+
+#[test]
+fn modular_ring_values() {
+    test_eval("2^100 (mod 13)", "3 (mod 13)");
+    test_eval("5 (mod 13) + 10", "2 (mod 13)");
+    test_eval("10 - 3 (mod 7)", "0 (mod 7)");
+    test_eval("-(2 (mod 5))", "3 (mod 5)");
+    test_eval("x = 4 (mod 9); 2x", "8 (mod 9)");
+    test_eval("-3 (mod 5)", "2 (mod 5)");
+}
+
+#[test]
+fn modular_division() {
+    test_eval("1 (mod 7) / 3", "5 (mod 7)");
+    test_eval("x = 3 (mod 7); x^-1", "5 (mod 7)");
+    test_eval("(1/10) (mod 17)", "12 (mod 17)");
+    expect_error("2 (mod 4) / 2", Some("modular inverse does not exist"));
+}
+
+#[test]
+fn modular_errors() {
+    expect_error(
+        "2 (mod 5) * 3 (mod 7)",
+        Some("cannot combine integers with different moduli"),
+    );
+    expect_error(
+        "2 (mod 5) mod 3",
+        Some("operation is not supported for modular integers"),
+    );
+    expect_error("2 (mod 0)", Some("modulo by zero"));
+    expect_error("1.5 (mod 7)", Some("cannot convert fraction to integer"));
+}
+
+#[test]
+fn complex_as_polar() {
+    test_eval("(3+4i) as polar", "approx. 5 ∠ 53.1301023542°");
+    test_eval("(1+i) as polar", "approx. 1.4142135619 ∠ 45°");
+    test_eval("-2 as polar", "2 ∠ 180°");
+    test_eval("(-3-4i) as polar to 2 dp", "approx. 5 ∠ -126.86°");
+}
+
+#[test]
+fn polar_input() {
+    test_eval("2 ∠ 90°", "2 ∠ 90°");
+    test_eval("5 ∠ 30°", "approx. 5 ∠ 30°");
+    test_eval("2 ∠ 100 gradians", "2 ∠ 90°");
+    test_eval("1 ∠ pi/2", "1 ∠ 90°");
+    test_eval("(10 ∠ 30°) * (2 ∠ 15°)", "approx. 20 ∠ 45°");
+    test_eval("(10 ∠ 30°) V", "approx. (10 ∠ 30°) V");
+    test_eval("(2 ∠ 90°) as cartesian", "2i");
+    expect_error("5 ∠ 3 m", Some("expected an angle, e.g. 30° or 0.5 rad"));
+}
+
+#[test]
+fn complex_arg() {
+    test_eval("arg (3+4i)", "approx. 0.927295218");
+    test_eval("arg (1 + i) to degrees", "45 degrees");
+    test_eval("arg (-1) to degrees", "180 degrees");
+    test_eval("arg (-i) to degrees", "-90 degrees");
+    test_eval("arg (1 - i)", "approx. -0.7853981633");
+    test_eval("arg (5 ∠ 120°) to 6 dp", "approx. 2.094395");
+    test_eval("abs (5 ∠ 30°)", "approx. 5");
+}
+
+#[test]
+fn add_and_subtract_percentages() {
+    test_eval("200 + 15%", "230");
+    test_eval("200 - 15%", "170");
+    test_eval("200 kg + 15%", "230 kg");
+    test_eval("200 + 15% + 10%", "253");
+    test_eval("5% + 10%", "15%");
+    test_eval("5% * 2 + 10%", "20%");
+    test_eval("a = 15%; 200 + a", "200.15");
+}
+
+#[test]
+fn percent_of() {
+    test_eval("15% of 200", "30");
+    test_eval("15% of 200 kg", "30 kg");
+    test_eval("5 percent of 60", "3");
+    test_eval("2 * 15% of 200", "60");
+    expect_error("% of 200", Some("expected a number"));
+}
+
+#[test]
+fn what_percent_of() {
+    test_eval("80 is what % of 200", "40%");
+    test_eval("80 kg is what percent of 200 kg", "40%");
+    expect_error("2 is what % of 0", Some("division by zero"));
+}
+
+#[test]
+fn percent_change() {
+    test_eval("percent change from 80 to 100", "25%");
+    test_eval("% change from 100 to 80", "-20%");
+    test_eval("percentage change from 4 kg to 5 kg", "25%");
+}
+
+#[test]
+fn percent_off() {
+    test_eval_simple("10% off 59.99 USD", "approx. 53.99 USD");
+    test_eval("25% off 80", "60");
+    test_eval("off = 5; 2 off", "10");
+}